@@ -0,0 +1,469 @@
+use entropy_engine::helpers::utilities::get_common_os_dir;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::error::{CommandError, CommandResult};
+use crate::models;
+use crate::projects::{self, load_saved_state, save_saved_state};
+
+pub const ARCHIVE_EXTENSION: &str = "entropy";
+const MANIFEST_NAME: &str = "manifest.json";
+const PROJECT_PREFIX: &str = "project/";
+const ASSETS_PREFIX: &str = "assets/";
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub format_version: u32,
+    pub project_id: String,
+    pub project_name: String,
+    pub exported_at: u64,
+    pub files: Vec<ArchiveEntry>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArchiveEntry {
+    pub path: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+pub(crate) fn asset_dir() -> CommandResult<PathBuf> {
+    get_common_os_dir().ok_or_else(|| CommandError::io("Failed to get asset directory", "no home directory"))
+}
+
+/// Only plain relative paths are accepted, so nothing in a SavedState or archive can point outside its root
+pub(crate) fn safe_relative_path(path: &str) -> Option<PathBuf> {
+    let path = Path::new(path);
+    if path.as_os_str().is_empty() {
+        return None;
+    }
+
+    path.components()
+        .all(|c| matches!(c, Component::Normal(_)))
+        .then(|| path.to_path_buf())
+}
+
+/// A manifest's project id names the project's directory, so it has to be a single plain path component
+fn safe_project_id(project_id: &str) -> bool {
+    let mut components = Path::new(project_id).components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(name)), None) if name == project_id
+    )
+}
+
+fn collect_strings<'a>(value: &'a serde_json::Value, out: &mut Vec<&'a str>) {
+    match value {
+        serde_json::Value::String(s) => out.push(s),
+        serde_json::Value::Array(items) => items.iter().for_each(|v| collect_strings(v, out)),
+        serde_json::Value::Object(map) => map.values().for_each(|v| collect_strings(v, out)),
+        _ => {}
+    }
+}
+
+fn rewrite_strings(value: &mut serde_json::Value, replacements: &HashMap<String, String>) {
+    match value {
+        serde_json::Value::String(s) => {
+            if let Some(replacement) = replacements.get(s.as_str()) {
+                *s = replacement.clone();
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(|v| rewrite_strings(v, replacements)),
+        serde_json::Value::Object(map) => map.values_mut().for_each(|v| rewrite_strings(v, replacements)),
+        _ => {}
+    }
+}
+
+/// Asset references aren't typed in `SavedState`, so any string which names a file in the asset directory counts
+fn referenced_assets(saved_state: &serde_json::Value, asset_dir: &Path) -> Vec<String> {
+    let mut strings = Vec::new();
+    collect_strings(saved_state, &mut strings);
+
+    let mut assets: Vec<String> = strings
+        .into_iter()
+        .filter(|s| safe_relative_path(s).map(|p| asset_dir.join(p).is_file()).unwrap_or(false))
+        .map(|s| s.to_string())
+        .collect();
+    assets.sort();
+    assets.dedup();
+    assets
+}
+
+/// The buffers and images a glTF asset loads from next to itself, as paths in the same root as `asset`
+fn model_dependencies(asset: &str, bytes: &[u8]) -> Vec<String> {
+    if !models::is_model_file(Path::new(asset)) {
+        return Vec::new();
+    }
+    let Some(gltf) = models::gltf_json(bytes) else {
+        return Vec::new();
+    };
+
+    let folder = asset.rsplit_once('/').map(|(folder, _)| format!("{}/", folder)).unwrap_or_default();
+    models::external_files(&gltf)
+        .into_iter()
+        .filter(|uri| safe_relative_path(uri).is_some())
+        .map(|uri| format!("{}{}", folder, uri.replace('\\', "/")))
+        .collect()
+}
+
+fn collect_files(dir: &Path, prefix: &str, out: &mut Vec<(String, PathBuf)>) -> CommandResult<()> {
+    for entry in fs::read_dir(dir).map_err(|e| CommandError::io("Failed to read directory", e))? {
+        let entry = entry.map_err(|e| CommandError::io("Failed to read directory entry", e))?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let path = entry.path();
+
+        if path.is_dir() {
+            collect_files(&path, &format!("{}{}/", prefix, name), out)?;
+        } else {
+            out.push((format!("{}{}", prefix, name), path));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn export_project(project_id: &str, destination: PathBuf) -> CommandResult<ArchiveManifest> {
+    let saved_state = load_saved_state(project_id).await?;
+    let saved_value = serde_json::to_value(&saved_state)?;
+
+    let asset_dir = asset_dir()?;
+    let project_dir = projects::projects_dir()?.join(project_id);
+
+    let mut assets = Vec::new();
+    for asset in referenced_assets(&saved_value, &asset_dir) {
+        let asset = asset.replace('\\', "/");
+        // a model is only whole with the buffers and textures it loads from next to itself
+        if let Ok(bytes) = fs::read(asset_dir.join(&asset)) {
+            assets.extend(
+                model_dependencies(&asset, &bytes)
+                    .into_iter()
+                    .filter(|dependency| asset_dir.join(dependency).is_file()),
+            );
+        }
+        assets.push(asset);
+    }
+    assets.sort();
+    assets.dedup();
+
+    let mut files = Vec::new();
+    collect_files(&project_dir, PROJECT_PREFIX, &mut files)?;
+    for asset in assets {
+        let source = asset_dir.join(&asset);
+        files.push((format!("{}{}", ASSETS_PREFIX, asset), source));
+    }
+
+    let project_id = project_id.to_string();
+    let project_name = saved_state.project_name.clone();
+
+    tokio::task::spawn_blocking(move || write_archive(&destination, project_id, project_name, files))
+        .await
+        .map_err(|e| CommandError::io("Export task failed", e))?
+}
+
+fn write_archive(
+    destination: &Path,
+    project_id: String,
+    project_name: String,
+    files: Vec<(String, PathBuf)>,
+) -> CommandResult<ArchiveManifest> {
+    let file = File::create(destination).map_err(|e| CommandError::io("Failed to create archive", e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default();
+
+    let mut entries = Vec::new();
+    for (archive_path, source) in files {
+        let bytes = fs::read(&source).map_err(|e| CommandError::io(&format!("Failed to read {:?}", source), e))?;
+
+        zip.start_file(archive_path.as_str(), options)
+            .map_err(|e| CommandError::io("Failed to write archive", e))?;
+        zip.write_all(&bytes).map_err(|e| CommandError::io("Failed to write archive", e))?;
+
+        entries.push(ArchiveEntry {
+            path: archive_path,
+            sha256: sha256_hex(&bytes),
+            size: bytes.len() as u64,
+        });
+    }
+
+    let manifest = ArchiveManifest {
+        format_version: FORMAT_VERSION,
+        project_id,
+        project_name,
+        exported_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        files: entries,
+    };
+
+    zip.start_file(MANIFEST_NAME, options)
+        .map_err(|e| CommandError::io("Failed to write archive", e))?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())
+        .map_err(|e| CommandError::io("Failed to write archive", e))?;
+    zip.finish().map_err(|e| CommandError::io("Failed to finish archive", e))?;
+
+    Ok(manifest)
+}
+
+/// Everything read out of an archive, checked against the manifest but not yet written anywhere
+struct UnpackedArchive {
+    manifest: ArchiveManifest,
+    project_files: BTreeMap<PathBuf, Vec<u8>>,
+    asset_files: BTreeMap<String, Vec<u8>>,
+}
+
+fn read_archive(source: &Path) -> CommandResult<UnpackedArchive> {
+    let file = File::open(source).map_err(|e| CommandError::io("Failed to open archive", e))?;
+    let mut zip = ZipArchive::new(file).map_err(|e| CommandError::Decode { message: e.to_string() })?;
+
+    let manifest: ArchiveManifest = {
+        let mut entry = zip
+            .by_name(MANIFEST_NAME)
+            .map_err(|_| CommandError::Decode { message: "Archive has no manifest".to_string() })?;
+        let mut contents = String::new();
+        entry
+            .read_to_string(&mut contents)
+            .map_err(|e| CommandError::io("Failed to read manifest", e))?;
+        serde_json::from_str(&contents)?
+    };
+
+    if !safe_project_id(&manifest.project_id) {
+        return Err(CommandError::Decode {
+            message: format!("Unsafe project id in archive: {:?}", manifest.project_id),
+        });
+    }
+
+    if manifest.format_version > FORMAT_VERSION {
+        return Err(CommandError::Decode {
+            message: format!("Archive format {} is newer than this app supports", manifest.format_version),
+        });
+    }
+
+    let mut project_files = BTreeMap::new();
+    let mut asset_files = BTreeMap::new();
+
+    for entry in &manifest.files {
+        let mut file = zip.by_name(&entry.path).map_err(|_| CommandError::Decode {
+            message: format!("Archive is missing {}", entry.path),
+        })?;
+        // the manifest's sizes are untrusted, so never read or allocate past what it claims
+        if file.size() != entry.size {
+            return Err(CommandError::Decode {
+                message: format!("Size mismatch for {}", entry.path),
+            });
+        }
+        let mut bytes = Vec::new();
+        file.by_ref()
+            .take(entry.size)
+            .read_to_end(&mut bytes)
+            .map_err(|e| CommandError::io("Failed to read archive entry", e))?;
+
+        if sha256_hex(&bytes) != entry.sha256 {
+            return Err(CommandError::Decode {
+                message: format!("Checksum mismatch for {}", entry.path),
+            });
+        }
+
+        if let Some(relative) = entry.path.strip_prefix(PROJECT_PREFIX) {
+            let relative = safe_relative_path(relative).ok_or_else(|| CommandError::Decode {
+                message: format!("Unsafe path in archive: {}", entry.path),
+            })?;
+            project_files.insert(relative, bytes);
+        } else if let Some(relative) = entry.path.strip_prefix(ASSETS_PREFIX) {
+            safe_relative_path(relative).ok_or_else(|| CommandError::Decode {
+                message: format!("Unsafe path in archive: {}", entry.path),
+            })?;
+            asset_files.insert(relative.to_string(), bytes);
+        }
+    }
+
+    Ok(UnpackedArchive { manifest, project_files, asset_files })
+}
+
+/// What an import has written so far, removed again when a later step fails
+struct ImportedFiles {
+    project_dir: PathBuf,
+    // folder holding the assets that clashed with different ones already there
+    owned_asset_dir: PathBuf,
+    shared_assets: Vec<PathBuf>,
+}
+
+impl ImportedFiles {
+    fn roll_back(&self) {
+        let _ = fs::remove_dir_all(&self.project_dir);
+        let _ = fs::remove_dir_all(&self.owned_asset_dir);
+        for asset in &self.shared_assets {
+            let _ = fs::remove_file(asset);
+        }
+    }
+}
+
+fn write_file(target: &Path, bytes: &[u8], what: &str) -> CommandResult<()> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| CommandError::io(&format!("Failed to create {} directory", what), e))?;
+    }
+    fs::write(target, bytes).map_err(|e| CommandError::io(&format!("Failed to write {} file", what), e))
+}
+
+/// Unpacks an archive as a new project, returning its local id and name
+pub async fn import_project(source: PathBuf) -> CommandResult<(String, String)> {
+    let unpacked = tokio::task::spawn_blocking(move || read_archive(&source))
+        .await
+        .map_err(|e| CommandError::io("Import task failed", e))??;
+
+    let projects_dir = projects::projects_dir()?;
+    let asset_dir = asset_dir()?;
+
+    let old_project_id = unpacked.manifest.project_id.clone();
+    let project_id = if projects_dir.join(&old_project_id).exists() {
+        Uuid::new_v4().to_string()
+    } else {
+        old_project_id.clone()
+    };
+
+    let owned_prefix = format!("imported/{}/", project_id);
+    let mut written = ImportedFiles {
+        project_dir: projects_dir.join(&project_id),
+        owned_asset_dir: asset_dir.join(&owned_prefix),
+        shared_assets: Vec::new(),
+    };
+
+    match unpack_project(&unpacked, &project_id, &old_project_id, &asset_dir, &owned_prefix, &mut written).await {
+        Ok(project_name) => Ok((project_id, project_name)),
+        Err(e) => {
+            written.roll_back();
+            Err(e)
+        }
+    }
+}
+
+async fn unpack_project(
+    unpacked: &UnpackedArchive,
+    project_id: &str,
+    old_project_id: &str,
+    asset_dir: &Path,
+    owned_prefix: &str,
+    written: &mut ImportedFiles,
+) -> CommandResult<String> {
+    // the project is written and read back first, so a bad SavedState leaves the asset directory alone
+    for (relative, bytes) in &unpacked.project_files {
+        write_file(&written.project_dir.join(relative), bytes, "project")?;
+    }
+    let saved_state = load_saved_state(project_id).await?;
+
+    let mut replacements = HashMap::new();
+    if project_id != old_project_id {
+        replacements.insert(old_project_id.to_string(), project_id.to_string());
+    }
+
+    // identical assets are shared, differing ones go under a folder owned by this import
+    let mut relocated = Vec::new();
+    for (relative, bytes) in &unpacked.asset_files {
+        let target = asset_dir.join(relative);
+        if target.is_file() {
+            let existing = fs::read(&target).map_err(|e| CommandError::io("Failed to read existing asset", e))?;
+            if sha256_hex(&existing) != sha256_hex(bytes) {
+                relocated.push(relative.clone());
+            }
+        }
+    }
+    // a relocated model still loads its buffers and textures from next to itself
+    for model in relocated.clone() {
+        if let Some(bytes) = unpacked.asset_files.get(&model) {
+            relocated.extend(
+                model_dependencies(&model, bytes)
+                    .into_iter()
+                    .filter(|dependency| unpacked.asset_files.contains_key(dependency)),
+            );
+        }
+    }
+
+    for (relative, bytes) in &unpacked.asset_files {
+        if relocated.contains(relative) {
+            let target_relative = format!("{}{}", owned_prefix, relative);
+            write_file(&asset_dir.join(&target_relative), bytes, "asset")?;
+            replacements.insert(relative.clone(), target_relative);
+        } else {
+            let target = asset_dir.join(relative);
+            if target.is_file() {
+                continue;
+            }
+            write_file(&target, bytes, "asset")?;
+            written.shared_assets.push(target);
+        }
+    }
+
+    if replacements.is_empty() {
+        return Ok(saved_state.project_name);
+    }
+
+    let mut saved_value = serde_json::to_value(&saved_state)?;
+    rewrite_strings(&mut saved_value, &replacements);
+    let saved_state = serde_json::from_value(saved_value)?;
+    save_saved_state(project_id, &saved_state).await?;
+
+    Ok(saved_state.project_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_plain_relative_paths() {
+        assert_eq!(safe_relative_path("models/tree.glb"), Some(PathBuf::from("models/tree.glb")));
+        assert_eq!(safe_relative_path("texture.png"), Some(PathBuf::from("texture.png")));
+    }
+
+    #[test]
+    fn rejects_paths_leaving_the_root() {
+        for path in ["", "../secret", "models/../../secret", "/etc/passwd", "./texture.png"] {
+            assert_eq!(safe_relative_path(path), None, "{:?} should be rejected", path);
+        }
+    }
+
+    #[test]
+    fn accepts_single_component_project_ids() {
+        assert!(safe_project_id("3f2c9a4e-8d1b-4c7a-9e0f-2b6d5a1c8e47"));
+        assert!(safe_project_id("my-project"));
+    }
+
+    #[test]
+    fn rejects_project_ids_naming_other_directories() {
+        for id in ["", ".", "..", "../other", "nested/project", "/tmp/project", "project/"] {
+            assert!(!safe_project_id(id), "{:?} should be rejected", id);
+        }
+    }
+
+    #[test]
+    fn model_dependencies_sit_next_to_the_model() {
+        let gltf = br#"{
+            "buffers": [{ "uri": "tree.bin" }, { "uri": "data:application/octet-stream;base64,AAAA" }],
+            "images": [{ "uri": "textures/bark%20dark.png" }, { "uri": "../escape.png" }]
+        }"#;
+
+        assert_eq!(
+            model_dependencies("models/tree/tree.gltf", gltf),
+            ["models/tree/textures/bark dark.png", "models/tree/tree.bin"]
+        );
+        assert_eq!(model_dependencies("tree.gltf", gltf), ["textures/bark dark.png", "tree.bin"]);
+    }
+
+    #[test]
+    fn other_assets_have_no_dependencies() {
+        assert!(model_dependencies("textures/bark.png", br#"{ "buffers": [{ "uri": "tree.bin" }] }"#).is_empty());
+    }
+}
@@ -0,0 +1,208 @@
+use entropy_engine::helpers::utilities::get_common_os_dir;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::http::{header, Request, Response, StatusCode};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+/// A single `bytes=` range, already resolved against the file length (end inclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ByteRange {
+    start: u64,
+    end: u64,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum RangeRequest {
+    Full,
+    Partial(ByteRange),
+    Unsatisfiable,
+}
+
+fn empty_response(status: StatusCode) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .body(Vec::new())
+        .expect("Couldn't create response")
+}
+
+/// Only single ranges are supported, which is all media elements and model loaders ask for
+fn parse_range(value: Option<&str>, len: u64) -> RangeRequest {
+    let Some(spec) = value.and_then(|v| v.trim().strip_prefix("bytes=")) else {
+        return RangeRequest::Full;
+    };
+
+    if spec.contains(',') || len == 0 {
+        return RangeRequest::Unsatisfiable;
+    }
+
+    let Some((start, end)) = spec.split_once('-') else {
+        return RangeRequest::Unsatisfiable;
+    };
+
+    let range = match (start.trim(), end.trim()) {
+        // last N bytes
+        ("", suffix) => match suffix.parse::<u64>() {
+            Ok(0) | Err(_) => None,
+            Ok(n) => Some(ByteRange { start: len.saturating_sub(n), end: len - 1 }),
+        },
+        (start, "") => start.parse::<u64>().ok().map(|start| ByteRange { start, end: len - 1 }),
+        (start, end) => match (start.parse::<u64>(), end.parse::<u64>()) {
+            (Ok(start), Ok(end)) if start <= end => Some(ByteRange { start, end: end.min(len - 1) }),
+            _ => None,
+        },
+    };
+
+    match range {
+        Some(range) if range.start < len => RangeRequest::Partial(range),
+        _ => RangeRequest::Unsatisfiable,
+    }
+}
+
+/// Resolves the request path inside the asset directory, refusing anything that escapes it
+async fn resolve_asset_path(request_path: &str) -> Result<PathBuf, StatusCode> {
+    let decoded_path = urlencoding::decode(request_path.trim_start_matches('/'))
+        .map_err(|_| StatusCode::BAD_REQUEST)?
+        .into_owned();
+
+    let asset_dir = get_common_os_dir().ok_or(StatusCode::NOT_FOUND)?;
+    let root = tokio::fs::canonicalize(&asset_dir)
+        .await
+        .map_err(|_| StatusCode::NOT_FOUND)?;
+
+    let asset_path = tokio::fs::canonicalize(root.join(&decoded_path))
+        .await
+        .map_err(|_| StatusCode::NOT_FOUND)?;
+
+    if !asset_path.starts_with(&root) {
+        println!("asset request outside the asset directory {:?}", decoded_path);
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    Ok(asset_path)
+}
+
+pub async fn handle_asset_request(request: Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let asset_path = match resolve_asset_path(request.uri().path()).await {
+        Ok(path) => path,
+        Err(status) => return empty_response(status),
+    };
+
+    let metadata = match tokio::fs::metadata(&asset_path).await {
+        Ok(metadata) if metadata.is_file() => metadata,
+        _ => return empty_response(StatusCode::NOT_FOUND),
+    };
+
+    let len = metadata.len();
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let etag = format!("\"{:x}-{:x}\"", len, modified);
+
+    let if_none_match = request
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok());
+    if if_none_match.map(|v| v.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*")).unwrap_or(false) {
+        return Response::builder()
+            .status(StatusCode::NOT_MODIFIED)
+            .header(header::ETAG, &etag)
+            .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+            .body(Vec::new())
+            .expect("Couldn't create response");
+    }
+
+    let mime_type = mime_guess::from_path(&asset_path).first_or_octet_stream();
+    let range_header = request.headers().get(header::RANGE).and_then(|v| v.to_str().ok());
+
+    let builder = Response::builder()
+        .header(header::CONTENT_TYPE, mime_type.to_string())
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::ETAG, &etag)
+        .header(header::CACHE_CONTROL, "no-cache")
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .header(header::ACCESS_CONTROL_EXPOSE_HEADERS, "Content-Range, Content-Length, ETag");
+
+    match parse_range(range_header, len) {
+        RangeRequest::Full => match tokio::fs::read(&asset_path).await {
+            Ok(content) => builder
+                .status(StatusCode::OK)
+                .header(header::CONTENT_LENGTH, content.len())
+                .body(content)
+                .expect("Couldn't create response"),
+            Err(e) => {
+                eprintln!("Could not read asset {:?}: {}", asset_path, e);
+                empty_response(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        },
+        RangeRequest::Partial(range) => match read_range(&asset_path, range).await {
+            Ok(content) => builder
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(header::CONTENT_RANGE, format!("bytes {}-{}/{}", range.start, range.end, len))
+                .header(header::CONTENT_LENGTH, content.len())
+                .body(content)
+                .expect("Couldn't create response"),
+            Err(e) => {
+                eprintln!("Could not read asset range {:?}: {}", asset_path, e);
+                empty_response(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        },
+        RangeRequest::Unsatisfiable => Response::builder()
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+            .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+            .body(Vec::new())
+            .expect("Couldn't create response"),
+    }
+}
+
+async fn read_range(path: &Path, range: ByteRange) -> std::io::Result<Vec<u8>> {
+    let mut file = tokio::fs::File::open(path).await?;
+    file.seek(SeekFrom::Start(range.start)).await?;
+
+    let mut content = vec![0; (range.end - range.start + 1) as usize];
+    file.read_exact(&mut content).await?;
+
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partial(start: u64, end: u64) -> RangeRequest {
+        RangeRequest::Partial(ByteRange { start, end })
+    }
+
+    #[test]
+    fn missing_or_foreign_header_is_full() {
+        assert_eq!(parse_range(None, 100), RangeRequest::Full);
+        assert_eq!(parse_range(Some("items=0-10"), 100), RangeRequest::Full);
+    }
+
+    #[test]
+    fn parses_bounded_and_open_ranges() {
+        assert_eq!(parse_range(Some("bytes=0-9"), 100), partial(0, 9));
+        assert_eq!(parse_range(Some(" bytes=50- "), 100), partial(50, 99));
+        // an end past the file is clamped rather than refused
+        assert_eq!(parse_range(Some("bytes=90-500"), 100), partial(90, 99));
+    }
+
+    #[test]
+    fn parses_suffix_ranges() {
+        assert_eq!(parse_range(Some("bytes=-10"), 100), partial(90, 99));
+        assert_eq!(parse_range(Some("bytes=-500"), 100), partial(0, 99));
+    }
+
+    #[test]
+    fn refuses_unsatisfiable_ranges() {
+        for spec in ["bytes=100-", "bytes=10-5", "bytes=-0", "bytes=0-1,5-6", "bytes=a-b", "bytes=5"] {
+            assert_eq!(parse_range(Some(spec), 100), RangeRequest::Unsatisfiable, "{:?}", spec);
+        }
+        assert_eq!(parse_range(Some("bytes=0-0"), 0), RangeRequest::Unsatisfiable);
+    }
+}
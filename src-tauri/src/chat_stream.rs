@@ -0,0 +1,363 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use uuid::Uuid;
+
+use crate::error::{CommandError, CommandResult};
+use crate::ChatMessage;
+
+pub const CHAT_STREAM_EVENT: &str = "chat-stream";
+
+/// Re-emitted to the frontend as the assistant's response arrives, tagged with the session it belongs to
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ChatStreamEvent {
+    #[serde(rename_all = "camelCase")]
+    Delta { session_id: String, content: String },
+    #[serde(rename_all = "camelCase")]
+    ToolCallReady { session_id: String, tool_call: serde_json::Value },
+    #[serde(rename_all = "camelCase")]
+    Done { session_id: String, message: ChatMessage },
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChatDelta {
+    id: Option<String>,
+    content: Option<String>,
+    tool_calls: Option<Vec<ToolCallDelta>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToolCallDelta {
+    #[serde(default)]
+    index: usize,
+    id: Option<String>,
+    r#type: Option<String>,
+    function: Option<FunctionDelta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FunctionDelta {
+    name: Option<String>,
+    arguments: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct PartialToolCall {
+    id: String,
+    r#type: String,
+    name: String,
+    arguments: String,
+    emitted: bool,
+}
+
+impl PartialToolCall {
+    fn to_value(&self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "type": if self.r#type.is_empty() { "function" } else { self.r#type.as_str() },
+            "function": {
+                "name": self.name,
+                "arguments": self.arguments,
+            },
+        })
+    }
+
+    // arguments are a JSON object, so they only parse once the closing brace has arrived
+    fn is_complete(&self) -> bool {
+        !self.id.is_empty()
+            && !self.name.is_empty()
+            && serde_json::from_str::<serde_json::Value>(&self.arguments).is_ok()
+    }
+}
+
+struct StreamState<'a> {
+    app: &'a AppHandle,
+    session_id: &'a str,
+    message_id: Option<String>,
+    content: String,
+    tool_calls: Vec<PartialToolCall>,
+    final_message: Option<ChatMessage>,
+}
+
+impl StreamState<'_> {
+    fn emit(&self, event: ChatStreamEvent) {
+        if let Err(e) = self.app.emit(CHAT_STREAM_EVENT, event) {
+            eprintln!("Could not emit chat stream event: {}", e);
+        }
+    }
+
+    fn apply(&mut self, event_name: &str, data: &str) -> CommandResult<()> {
+        if data == "[DONE]" {
+            return Ok(());
+        }
+
+        // servers may finish with the complete message instead of relying on the accumulated deltas
+        if event_name == "message" || event_name == "done" {
+            self.final_message = Some(serde_json::from_str::<ChatMessage>(data)?);
+            return Ok(());
+        }
+
+        let delta: ChatDelta = serde_json::from_str(data)?;
+
+        if self.message_id.is_none() {
+            self.message_id = delta.id;
+        }
+
+        if let Some(content) = delta.content.filter(|c| !c.is_empty()) {
+            self.content.push_str(&content);
+            self.emit(ChatStreamEvent::Delta {
+                session_id: self.session_id.to_string(),
+                content,
+            });
+        }
+
+        for tool_delta in delta.tool_calls.unwrap_or_default() {
+            if self.tool_calls.len() <= tool_delta.index {
+                self.tool_calls.resize_with(tool_delta.index + 1, PartialToolCall::default);
+            }
+
+            let tool_call = &mut self.tool_calls[tool_delta.index];
+            if let Some(id) = tool_delta.id {
+                tool_call.id = id;
+            }
+            if let Some(r#type) = tool_delta.r#type {
+                tool_call.r#type = r#type;
+            }
+            if let Some(function) = tool_delta.function {
+                if let Some(name) = function.name {
+                    tool_call.name.push_str(&name);
+                }
+                if let Some(arguments) = function.arguments {
+                    tool_call.arguments.push_str(&arguments);
+                }
+            }
+        }
+
+        self.emit_ready_tool_calls();
+
+        Ok(())
+    }
+
+    fn emit_ready_tool_calls(&mut self) {
+        let mut ready = Vec::new();
+        for tool_call in self.tool_calls.iter_mut() {
+            if !tool_call.emitted && tool_call.is_complete() {
+                tool_call.emitted = true;
+                ready.push(tool_call.to_value());
+            }
+        }
+
+        for tool_call in ready {
+            self.emit(ChatStreamEvent::ToolCallReady {
+                session_id: self.session_id.to_string(),
+                tool_call,
+            });
+        }
+    }
+
+    fn finish(self) -> ChatMessage {
+        if let Some(message) = self.final_message {
+            return message;
+        }
+
+        let tool_calls: Vec<serde_json::Value> = self.tool_calls.iter().map(|t| t.to_value()).collect();
+
+        ChatMessage {
+            id: self.message_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+            role: "assistant".to_string(),
+            content: (!self.content.is_empty()).then_some(self.content),
+            tool_call_id: None,
+            tool_calls: (!tool_calls.is_empty()).then(|| serde_json::Value::Array(tool_calls)),
+        }
+    }
+}
+
+/// One server-sent event, its `data:` lines joined
+#[derive(Debug, PartialEq, Eq)]
+struct SseEvent {
+    name: String,
+    data: String,
+}
+
+/// Splits a byte stream into SSE events, only decoding an event once all of it has arrived
+/// so multibyte characters split across chunks stay intact
+#[derive(Default)]
+struct SseDecoder {
+    buffer: Vec<u8>,
+}
+
+impl SseDecoder {
+    fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+        // normalize so events split the same way whichever line endings the server uses,
+        // a trailing '\r' waits for the next chunk in case its '\n' is there
+        if self.buffer.contains(&b'\r') {
+            let buffer = std::mem::take(&mut self.buffer);
+            self.buffer = buffer
+                .iter()
+                .enumerate()
+                .filter(|(i, b)| **b != b'\r' || buffer.get(i + 1) != Some(&b'\n'))
+                .map(|(_, b)| *b)
+                .collect();
+        }
+
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.windows(2).position(|w| w == b"\n\n") {
+            let raw_event: Vec<u8> = self.buffer.drain(..end + 2).collect();
+            if let Some(event) = parse_event(&String::from_utf8_lossy(&raw_event)) {
+                events.push(event);
+            }
+        }
+
+        events
+    }
+
+    /// The last event when the stream ended without the blank line closing it
+    fn finish(&mut self) -> Option<SseEvent> {
+        let raw_event = std::mem::take(&mut self.buffer);
+        parse_event(&String::from_utf8_lossy(&raw_event))
+    }
+}
+
+fn parse_event(raw_event: &str) -> Option<SseEvent> {
+    let mut name = String::new();
+    let mut data = Vec::new();
+    for line in raw_event.lines() {
+        if let Some(value) = line.strip_prefix("event:") {
+            name = value.trim().to_string();
+        } else if let Some(value) = line.strip_prefix("data:") {
+            data.push(value.strip_prefix(' ').unwrap_or(value));
+        }
+    }
+
+    (!data.is_empty()).then(|| SseEvent { name, data: data.join("\n") })
+}
+
+fn is_event_stream(response: &reqwest::Response) -> bool {
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.starts_with("text/event-stream"))
+        .unwrap_or(false)
+}
+
+/// Reads a successful `/messages` response, streaming it when the server sent SSE and decoding it whole otherwise
+pub async fn read_response(
+    app: &AppHandle,
+    session_id: &str,
+    mut response: reqwest::Response,
+) -> CommandResult<ChatMessage> {
+    if !is_event_stream(&response) {
+        let message = response.json::<ChatMessage>().await?;
+        if let Err(e) = app.emit(CHAT_STREAM_EVENT, ChatStreamEvent::Done {
+            session_id: session_id.to_string(),
+            message: message.clone(),
+        }) {
+            eprintln!("Could not emit chat stream event: {}", e);
+        }
+        return Ok(message);
+    }
+
+    let mut state = StreamState {
+        app,
+        session_id,
+        message_id: None,
+        content: String::new(),
+        tool_calls: Vec::new(),
+        final_message: None,
+    };
+
+    let mut decoder = SseDecoder::default();
+    let mut ended = false;
+    while !ended {
+        let events = match response.chunk().await? {
+            Some(chunk) => decoder.push(&chunk),
+            None => {
+                ended = true;
+                decoder.finish().into_iter().collect()
+            }
+        };
+
+        for event in events {
+            state.apply(&event.name, &event.data).map_err(|e| match e {
+                CommandError::Decode { message } => CommandError::Decode {
+                    message: format!("Bad stream event: {}", message),
+                },
+                other => other,
+            })?;
+        }
+    }
+
+    // tool calls still pending when the stream ends are as complete as they will get
+    state.emit_ready_tool_calls();
+
+    let message = state.finish();
+    if let Err(e) = app.emit(CHAT_STREAM_EVENT, ChatStreamEvent::Done {
+        session_id: session_id.to_string(),
+        message: message.clone(),
+    }) {
+        eprintln!("Could not emit chat stream event: {}", e);
+    }
+
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(name: &str, data: &str) -> SseEvent {
+        SseEvent { name: name.to_string(), data: data.to_string() }
+    }
+
+    #[test]
+    fn waits_for_the_blank_line_ending_an_event() {
+        let mut decoder = SseDecoder::default();
+
+        assert!(decoder.push(b"data: {\"content\"").is_empty());
+        assert!(decoder.push(b": \"hi\"}\n").is_empty());
+        assert_eq!(decoder.push(b"\ndata: [DONE]\n\n"), [
+            event("", "{\"content\": \"hi\"}"),
+            event("", "[DONE]"),
+        ]);
+    }
+
+    #[test]
+    fn keeps_multibyte_characters_split_across_chunks() {
+        let bytes = "data: caf\u{e9} \u{1f30a}\n\n".as_bytes();
+        let split = bytes.iter().position(|b| *b == 0xf0).unwrap() + 2;
+
+        let mut decoder = SseDecoder::default();
+        assert!(decoder.push(&bytes[..split]).is_empty());
+        assert_eq!(decoder.push(&bytes[split..]), [event("", "caf\u{e9} \u{1f30a}")]);
+    }
+
+    #[test]
+    fn handles_crlf_split_across_chunks() {
+        let mut decoder = SseDecoder::default();
+
+        assert!(decoder.push(b"event: done\r\ndata: {}\r").is_empty());
+        assert!(decoder.push(b"\n\r").is_empty());
+        assert_eq!(decoder.push(b"\n"), [event("done", "{}")]);
+    }
+
+    #[test]
+    fn joins_data_lines_and_skips_events_without_data() {
+        let mut decoder = SseDecoder::default();
+
+        assert_eq!(decoder.push(b": keep-alive\n\nevent: message\ndata: a\ndata:b\n\n"), [
+            event("message", "a\nb"),
+        ]);
+    }
+
+    #[test]
+    fn flushes_a_last_event_without_a_blank_line() {
+        let mut decoder = SseDecoder::default();
+
+        assert_eq!(decoder.push(b"data: one\n\ndata: two\n"), [event("", "one")]);
+        assert_eq!(decoder.finish(), Some(event("", "two")));
+        assert_eq!(decoder.finish(), None);
+    }
+}
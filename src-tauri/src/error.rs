@@ -0,0 +1,103 @@
+use serde::Serialize;
+use thiserror::Error;
+
+/// Every command returns this so the frontend can tell failures apart instead of parsing strings
+#[derive(Debug, Error, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum CommandError {
+    #[error("Couldn't reach entropy-api at {url}: {message}")]
+    ApiUnreachable { url: String, message: String },
+
+    #[error("entropy-api responded with {code}: {body}")]
+    ApiStatus { code: u16, body: String },
+
+    #[error("Project not found: {project_id}")]
+    ProjectNotFound { project_id: String },
+
+    #[error("Saved state for project {project_id} is corrupt: {message}")]
+    SavedStateCorrupt { project_id: String, message: String },
+
+    #[error("Invalid values: {}", format_issues(.issues))]
+    Validation { issues: Vec<ValidationIssue> },
+
+    #[error("Couldn't decode response: {message}")]
+    Decode { message: String },
+
+    #[error("Invalid settings: {message}")]
+    InvalidSettings { message: String },
+
+    #[error("{message}")]
+    Io { message: String },
+}
+
+pub type CommandResult<T> = Result<T, CommandError>;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ValidationIssue {
+    pub field: String,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        ValidationIssue {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+fn format_issues(issues: &[ValidationIssue]) -> String {
+    issues
+        .iter()
+        .map(|i| format!("{} {}", i.field, i.message))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl CommandError {
+    pub fn io(context: &str, e: impl std::fmt::Display) -> Self {
+        CommandError::Io {
+            message: format!("{}: {}", context, e),
+        }
+    }
+}
+
+impl From<reqwest::Error> for CommandError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_decode() {
+            return CommandError::Decode { message: e.to_string() };
+        }
+
+        if let Some(status) = e.status() {
+            return CommandError::ApiStatus {
+                code: status.as_u16(),
+                body: e.to_string(),
+            };
+        }
+
+        CommandError::ApiUnreachable {
+            url: e.url().map(|u| u.to_string()).unwrap_or_default(),
+            message: e.to_string(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for CommandError {
+    fn from(e: serde_json::Error) -> Self {
+        CommandError::Decode { message: e.to_string() }
+    }
+}
+
+/// Turns a non-success response into `ApiStatus`, keeping the body for display
+pub async fn check_status(response: reqwest::Response) -> CommandResult<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    Err(CommandError::ApiStatus {
+        code: status.as_u16(),
+        body: response.text().await.unwrap_or_default(),
+    })
+}
@@ -0,0 +1,37 @@
+use std::fs;
+
+use crate::error::{CommandError, CommandResult};
+use crate::projects::projects_dir;
+
+const EXTRAS_FILE_NAME: &str = "extras.json";
+
+/// Per-project settings `SavedState` has no fields for, stored next to it in sections the frontend owns
+pub type ProjectExtras = serde_json::Map<String, serde_json::Value>;
+
+/// A project without an extras file simply has none yet
+pub fn load_extras(project_id: &str) -> CommandResult<ProjectExtras> {
+    let project_dir = projects_dir()?.join(project_id);
+    if !project_dir.is_dir() {
+        return Err(CommandError::ProjectNotFound {
+            project_id: project_id.to_string(),
+        });
+    }
+
+    let path = project_dir.join(EXTRAS_FILE_NAME);
+    if !path.is_file() {
+        return Ok(ProjectExtras::new());
+    }
+
+    let contents = fs::read_to_string(&path).map_err(|e| CommandError::io("Failed to read project extras", e))?;
+    serde_json::from_str(&contents).map_err(|e| CommandError::SavedStateCorrupt {
+        project_id: project_id.to_string(),
+        message: format!("{} is invalid: {}", EXTRAS_FILE_NAME, e),
+    })
+}
+
+pub fn save_extras(project_id: &str, extras: &ProjectExtras) -> CommandResult<()> {
+    let path = projects_dir()?.join(project_id).join(EXTRAS_FILE_NAME);
+    let contents = serde_json::to_string_pretty(extras)?;
+
+    fs::write(&path, contents).map_err(|e| CommandError::io("Failed to write project extras", e))
+}
@@ -1,25 +1,39 @@
 use serde::{Deserialize, Serialize};
-use entropy_engine::helpers::utilities::{self, load_project_state};
+use entropy_engine::helpers::utilities;
 use entropy_engine::helpers::saved_data::{self, SavedState};
-use entropy_engine::water_plane::config::WaterConfig;
 use entropy_engine::handlers;
 use std::{fs, path::Path};
 use tauri::State;
-use reqwest::Client;
 use std::collections::HashMap;
-use tauri::{
-    http::{Response},
-    AppHandle,
-};
+use tauri::AppHandle;
 use std::path::PathBuf;
-use mime_guess;
-use entropy_engine::helpers::utilities::get_common_os_dir;
-
-#[derive(Clone, Debug, Serialize, Deserialize)]
+use tauri::Manager;
+use uuid::Uuid;
+
+mod archive;
+mod asset_protocol;
+mod chat_stream;
+mod error;
+mod extras;
+mod models;
+mod project_index;
+mod projects;
+mod settings;
+mod water;
+
+use error::{check_status, CommandError, CommandResult, ValidationIssue};
+use projects::load_saved_state;
+use settings::{AppSettings, SettingsState, SettingsView};
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ProjectInfo {
     pub id: String,
     pub name: String,
     pub path: String,
+    // the rest comes from the project index and is only filled in by list_projects
+    pub modified: Option<u64>,
+    pub component_count: Option<usize>,
+    pub thumbnail: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -63,60 +77,326 @@ pub struct OpenChatResponse {
 }
 
 #[tauri::command]
-async fn list_projects() -> Result<Vec<ProjectInfo>, String> {
+async fn list_projects() -> CommandResult<Vec<ProjectInfo>> {
     println!("listing projects...");
 
-    let mut projects_info = Vec::new();
-
-    let projects_dir = utilities::get_projects_dir()
-        .ok_or_else(|| "Failed to get projects directory".to_string())?;
-
-    for entry in fs::read_dir(projects_dir).map_err(|e| format!("Failed to read projects directory: {}", e))? {
-        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
-        let path = entry.path();
-
-        if path.is_dir() {
-            if let Some(project_id) = path.file_name().and_then(|s| s.to_str()) {
-                match utilities::load_project_state(project_id).await {
-                    Ok(saved_state) => {
-                        projects_info.push(ProjectInfo {
-                            id: project_id.to_string(),
-                            name: saved_state.project_name.clone(),
-                            path: project_id.to_string().clone(),
-                        });
-                    },
-                    Err(e) => {
-                        eprintln!("Could not load project state for project {}: {}", project_id, e);
-                    }
-                }
+    let index = project_index::load_index().await?;
+
+    Ok(index
+        .into_iter()
+        .map(|entry| ProjectInfo {
+            id: entry.id.clone(),
+            name: entry.name,
+            path: entry.id,
+            modified: Some(entry.modified),
+            component_count: Some(entry.component_count),
+            thumbnail: entry.thumbnail,
+        })
+        .collect())
+}
+
+#[tauri::command]
+async fn create_project(project_name: String) -> CommandResult<ProjectInfo> {
+    println!("create_project {:?}", project_name);
+
+    let project_name = project_name.trim().to_string();
+    if project_name.is_empty() {
+        return Err(CommandError::Validation {
+            issues: vec![ValidationIssue::new("project_name", "must not be empty")],
+        });
+    }
+
+    let project_id = Uuid::new_v4().to_string();
+    let project_dir = projects::projects_dir()?.join(&project_id);
+
+    fs::create_dir_all(&project_dir)
+        .map_err(|e| CommandError::io("Failed to create project directory", e))?;
+
+    let saved_state = projects::starter_saved_state(&project_id, &project_name)?;
+    if let Err(e) = projects::save_saved_state(&project_id, &saved_state).await {
+        // don't leave a directory behind that list_projects can't load
+        let _ = fs::remove_dir_all(&project_dir);
+        return Err(e);
+    }
+
+    Ok(ProjectInfo {
+        id: project_id.clone(),
+        name: project_name,
+        path: project_id,
+        ..Default::default()
+    })
+}
+
+#[tauri::command]
+async fn rename_project(project_id: String, project_name: String) -> CommandResult<ProjectInfo> {
+    println!("rename_project {:?} {:?}", project_id, project_name);
+
+    let project_name = project_name.trim().to_string();
+    if project_name.is_empty() {
+        return Err(CommandError::Validation {
+            issues: vec![ValidationIssue::new("project_name", "must not be empty")],
+        });
+    }
+
+    let mut saved_state = load_saved_state(&project_id).await?;
+    saved_state.project_name = project_name.clone();
+    projects::save_saved_state(&project_id, &saved_state).await?;
+
+    Ok(ProjectInfo {
+        id: project_id.clone(),
+        name: project_name,
+        path: project_id,
+        ..Default::default()
+    })
+}
+
+#[tauri::command]
+async fn duplicate_project(project_id: String) -> CommandResult<ProjectInfo> {
+    println!("duplicate_project {:?}", project_id);
+
+    let saved_state = load_saved_state(&project_id).await?;
+
+    let projects_dir = projects::projects_dir()?;
+    let new_project_id = Uuid::new_v4().to_string();
+    let new_project_dir = projects_dir.join(&new_project_id);
+
+    projects::copy_dir_all(&projects_dir.join(&project_id), &new_project_dir)?;
+    // describes the original, rebuilt below once the copy has its own id and name
+    let _ = fs::remove_file(new_project_dir.join(project_index::INDEX_FILE_NAME));
+
+    let project_name = format!("{} (Copy)", saved_state.project_name);
+    let new_state = match projects::with_identity(saved_state, &new_project_id, &project_name) {
+        Ok(new_state) => new_state,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&new_project_dir);
+            return Err(e);
+        }
+    };
+    if let Err(e) = projects::save_saved_state(&new_project_id, &new_state).await {
+        let _ = fs::remove_dir_all(&new_project_dir);
+        return Err(e);
+    }
+
+    if let Err(e) = project_index::refresh_index(&new_project_id).await {
+        eprintln!("Could not index duplicated project {}: {}", new_project_id, e);
+    }
+
+    Ok(ProjectInfo {
+        id: new_project_id.clone(),
+        name: new_state.project_name,
+        path: new_project_id,
+        ..Default::default()
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteProjectResponse {
+    // false when entropy-api couldn't be told, so the server-side project may linger
+    api_synced: bool,
+}
+
+#[tauri::command]
+async fn delete_project(
+    project_id: String,
+    settings: State<'_, SettingsState>,
+) -> CommandResult<DeleteProjectResponse> {
+    println!("delete_project {:?}", project_id);
+
+    let saved_state = load_saved_state(&project_id).await?;
+    projects::move_to_trash(&project_id, &saved_state.project_name)?;
+
+    let api_synced = match delete_api_project(&project_id, &settings).await {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("Could not delete entropy-api project for {}: {}", project_id, e);
+            false
+        }
+    };
+
+    Ok(DeleteProjectResponse { api_synced })
+}
+
+/// Removes the server-side project that `open_project_chat` created for this local project, if any
+async fn delete_api_project(project_path: &str, settings: &SettingsState) -> CommandResult<()> {
+    let client = settings.client();
+    let api_url = settings.api_url(Some(project_path));
+
+    let project_response = client
+        .get(format!("{}/projects/byPath?path={}", api_url, urlencoding::encode(project_path)))
+        .send()
+        .await?;
+
+    if project_response.status() == reqwest::StatusCode::NOT_FOUND {
+        // never opened in chat, nothing to clean up
+        return Ok(());
+    }
+
+    let project = check_status(project_response).await?.json::<Project>().await?;
+
+    let delete_response = client
+        .delete(format!("{}/projects/{}", api_url, project.id))
+        .send()
+        .await?;
+
+    check_status(delete_response).await?;
+
+    Ok(())
+}
+
+#[tauri::command]
+fn list_trash() -> CommandResult<Vec<projects::TrashedProject>> {
+    projects::list_trash()
+}
+
+#[tauri::command]
+async fn restore_project(project_id: String) -> CommandResult<ProjectInfo> {
+    println!("restore_project {:?}", project_id);
+
+    projects::restore_from_trash(&project_id)?;
+    let saved_state = load_saved_state(&project_id).await?;
+
+    Ok(ProjectInfo {
+        id: project_id.clone(),
+        name: saved_state.project_name,
+        path: project_id,
+        ..Default::default()
+    })
+}
+
+#[tauri::command]
+fn empty_trash() -> CommandResult<()> {
+    println!("empty_trash");
+
+    projects::empty_trash()
+}
+
+/// Writes the project and every asset it references to one archive; `None` when the dialog is cancelled
+#[tauri::command]
+async fn export_project_archive(project_id: String, destination: Option<String>) -> CommandResult<Option<String>> {
+    println!("export_project_archive {:?} {:?}", project_id, destination);
+
+    let destination = match destination {
+        Some(destination) => PathBuf::from(destination),
+        None => {
+            let saved_state = load_saved_state(&project_id).await?;
+            let handle = rfd::AsyncFileDialog::new()
+                .set_title("Export Project")
+                .set_file_name(format!("{}.{}", saved_state.project_name, archive::ARCHIVE_EXTENSION))
+                .add_filter("Entropy Project", &[archive::ARCHIVE_EXTENSION])
+                .save_file()
+                .await;
+
+            match handle {
+                Some(handle) => handle.path().to_path_buf(),
+                None => return Ok(None),
+            }
+        }
+    };
+
+    let manifest = archive::export_project(&project_id, destination.clone()).await?;
+    println!("exported {} files to {:?}", manifest.files.len(), destination);
+
+    Ok(Some(destination.to_string_lossy().to_string()))
+}
+
+#[tauri::command]
+async fn import_project_archive(source: Option<String>) -> CommandResult<Option<ProjectInfo>> {
+    println!("import_project_archive {:?}", source);
+
+    let source = match source {
+        Some(source) => PathBuf::from(source),
+        None => {
+            let handle = rfd::AsyncFileDialog::new()
+                .set_title("Import Project")
+                .add_filter("Entropy Project", &[archive::ARCHIVE_EXTENSION])
+                .pick_file()
+                .await;
+
+            match handle {
+                Some(handle) => handle.path().to_path_buf(),
+                None => return Ok(None),
             }
         }
+    };
+
+    let (project_id, project_name) = archive::import_project(source).await?;
+
+    Ok(Some(ProjectInfo {
+        id: project_id.clone(),
+        name: project_name,
+        path: project_id,
+        ..Default::default()
+    }))
+}
+
+#[tauri::command]
+async fn import_model(
+    project_id: String,
+    source: Option<String>,
+    library_id: Option<String>,
+) -> CommandResult<Option<models::ImportedModel>> {
+    println!("import_model {:?} {:?} {:?}", project_id, source, library_id);
+
+    if !projects::projects_dir()?.join(&project_id).is_dir() {
+        return Err(CommandError::ProjectNotFound { project_id });
     }
 
-    Ok(projects_info)
+    let source = match (&library_id, source) {
+        (Some(_), Some(_)) => {
+            return Err(CommandError::Validation {
+                issues: vec![ValidationIssue::new("libraryId", "can't be used together with source")],
+            });
+        }
+        (Some(_), None) => None,
+        (None, Some(source)) => Some(PathBuf::from(source)),
+        (None, None) => {
+            let handle = rfd::AsyncFileDialog::new()
+                .set_title("Import Model")
+                .add_filter("glTF Model", &models::MODEL_EXTENSIONS)
+                .pick_file()
+                .await;
+
+            match handle {
+                Some(handle) => Some(handle.path().to_path_buf()),
+                None => return Ok(None),
+            }
+        }
+    };
+
+    let model = match (library_id, source) {
+        (Some(library_id), _) => models::library_model(&library_id)?,
+        (None, Some(source)) => tokio::task::spawn_blocking(move || models::copy_model(&source))
+            .await
+            .map_err(|e| CommandError::io("Import task failed", e))??,
+        (None, None) => return Ok(None),
+    };
+
+    println!("imported model {:?} with {} files", model.asset_id, model.files.len());
+
+    Ok(Some(model))
 }
 
 #[tauri::command]
 async fn open_project_chat(
     project_name: String,
     project_path: String,
-    client: State<'_, Client>,
-) -> Result<OpenChatResponse, String> {
+    settings: State<'_, SettingsState>,
+) -> CommandResult<OpenChatResponse> {
     println!("open_project_chat {:?} {:?}", project_name, project_path);
 
-    let api_url = "http://localhost:3000";
+    let client = settings.client();
+    let api_url = settings.api_url(Some(&project_path));
 
     // Check if project exists
     let project_response = client
         .get(format!("{}/projects/byPath?path={}", api_url, urlencoding::encode(&project_path)))
         .send()
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
 
     println!("project response {:?}", project_response.status());
 
     let project = if project_response.status().is_success() {
-        project_response.json::<Project>().await.map_err(|e| e.to_string())?
+        project_response.json::<Project>().await?
     } else {
         // Create project if it doesn't exist
         let mut payload = HashMap::new();
@@ -127,14 +407,9 @@ async fn open_project_chat(
             .post(format!("{}/projects", api_url))
             .json(&payload)
             .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
 
-        if create_response.status().is_success() {
-            create_response.json::<Project>().await.map_err(|e| e.to_string())?
-        } else {
-            return Err(format!("Failed to create project: {}", create_response.text().await.unwrap_or_default()));
-        }
+        check_status(create_response).await?.json::<Project>().await?
     };
 
     println!("open_project_chat project {:?}", project.id);
@@ -143,37 +418,55 @@ async fn open_project_chat(
     let session_response = client
         .post(format!("{}/projects/{}/sessions", api_url, project.id))
         .send()
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
 
     println!("open_project_chat session_response {:?}", session_response.status());
 
-    if session_response.status().is_success() {
-        let session = session_response.json::<ChatSession>().await.map_err(|e| e.to_string())?;
-        Ok(OpenChatResponse {
-            project, session
-        })
-    } else {
-        Err(format!("Failed to create chat session: {}", session_response.text().await.unwrap_or_default()))
-    }
+    let session = check_status(session_response).await?.json::<ChatSession>().await?;
+
+    Ok(OpenChatResponse {
+        project, session
+    })
 }
 
 #[tauri::command]
-async fn get_chat_messages(session_id: String, client: State<'_, Client>) -> Result<Vec<ChatMessage>, String> {
+async fn get_chat_messages(
+    session_id: String,
+    project_id: Option<String>,
+    settings: State<'_, SettingsState>,
+) -> CommandResult<Vec<ChatMessage>> {
     println!("get_chat_messages {:?}", session_id);
 
-    let api_url = "http://localhost:3000";
+    let client = settings.client();
+    let api_url = settings.api_url(project_id.as_deref());
     let response = client
         .get(format!("{}/sessions/{}/messages", api_url, session_id))
         .send()
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
 
-    if response.status().is_success() {
-        response.json::<Vec<ChatMessage>>().await.map_err(|e| e.to_string())
-    } else {
-        Err(format!("Failed to get chat messages: {}", response.text().await.unwrap_or_default()))
-    }
+    Ok(check_status(response).await?.json::<Vec<ChatMessage>>().await?)
+}
+
+#[tauri::command]
+async fn publish_tool_catalog(
+    session_id: String,
+    project_id: Option<String>,
+    tools: serde_json::Value,
+    settings: State<'_, SettingsState>,
+) -> CommandResult<()> {
+    println!("publish_tool_catalog {:?}", session_id);
+
+    let client = settings.client();
+    let api_url = settings.api_url(project_id.as_deref());
+    let response = client
+        .put(format!("{}/sessions/{}/tools", api_url, session_id))
+        .json(&serde_json::json!({ "tools": tools }))
+        .send()
+        .await?;
+
+    check_status(response).await?;
+
+    Ok(())
 }
 
 #[tauri::command]
@@ -183,36 +476,51 @@ async fn send_message(
     content: String,
     tool_call_id: Option<String>,
     project_id: String,
-    client: State<'_, Client>,
-) -> Result<ChatMessage, String> {
+    app: AppHandle,
+    settings: State<'_, SettingsState>,
+) -> CommandResult<ChatMessage> {
     println!("send_message {:?} {:?} {:?} {:?} {:?}", session_id, role, content, tool_call_id, project_id);
 
-    let api_url = "http://localhost:3000";
+    let client = settings.stream_client();
+    let api_url = settings.api_url(Some(&project_id));
     let mut payload = HashMap::<&str, serde_json::Value>::new();
-    payload.insert("role", serde_json::to_value(role).unwrap());
-    payload.insert("content", serde_json::to_value(content).unwrap());
+    payload.insert("role", serde_json::Value::String(role));
+    payload.insert("content", serde_json::Value::String(content));
 
-    let saved_state = load_project_state(&project_id).await;
-    let saved_state = saved_state.as_ref().expect("Couldn't load saved state");
+    let saved_state = load_saved_state(&project_id).await?;
 
-    payload.insert("saved_state", serde_json::to_value(saved_state).unwrap());
+    payload.insert("saved_state", serde_json::to_value(&saved_state)?);
 
     if let Some(id) = tool_call_id {
-        payload.insert("tool_call_id", serde_json::to_value(id).unwrap());
+        payload.insert("tool_call_id", serde_json::Value::String(id));
     }
 
+    // servers which can't stream ignore both and answer with plain JSON
+    payload.insert("stream", serde_json::Value::Bool(true));
+
     let response = client
         .post(format!("{}/sessions/{}/messages", api_url, session_id))
+        .header(reqwest::header::ACCEPT, "text/event-stream, application/json")
         .json(&payload)
         .send()
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
 
-    if response.status().is_success() {
-        response.json::<ChatMessage>().await.map_err(|e| e.to_string())
-    } else {
-        Err(format!("Failed to send message: {}", response.text().await.unwrap_or_default()))
-    }
+    chat_stream::read_response(&app, &session_id, check_status(response).await?).await
+}
+
+#[tauri::command]
+fn get_settings(settings: State<'_, SettingsState>) -> CommandResult<SettingsView> {
+    Ok(settings.view())
+}
+
+#[tauri::command]
+fn update_settings(
+    new_settings: AppSettings,
+    settings: State<'_, SettingsState>,
+) -> CommandResult<SettingsView> {
+    println!("update_settings {:?}", new_settings);
+
+    settings.update(new_settings)
 }
 
 #[tauri::command]
@@ -222,92 +530,65 @@ fn log_message(message: String) {
 
 
 #[tauri::command]
-async fn configure_water_plane(
+async fn save_project(
     project_id: String,
-    config: WaterConfig,
-) -> Result<(), String> {
-    println!("configure_water_plane: project_id {:?}, config {:?}", project_id, config);
+    saved_state: SavedState,
+    extras: Option<extras::ProjectExtras>,
+) -> CommandResult<()> {
+    println!("save_project {:?}", project_id);
 
-    let mut saved_state =
-        utilities::load_project_state(&project_id)
-            .await
-            .map_err(|e| format!("Failed to load project state: {}", e))?;
+    if !projects::projects_dir()?.join(&project_id).is_dir() {
+        return Err(CommandError::ProjectNotFound { project_id });
+    }
 
-    let levels = saved_state.levels.as_mut().expect("Couldn't get levels");
+    let issues: Vec<ValidationIssue> = saved_state
+        .levels
+        .iter()
+        .flatten()
+        .filter_map(|level| level.components.as_ref())
+        .flatten()
+        .filter_map(|component| component.water_properties.as_ref().map(|config| (component, config)))
+        .flat_map(|(component, config)| {
+            water::validate_water_config(config).into_iter().map(|issue| {
+                ValidationIssue::new(format!("{}.water_properties.{}", component.id, issue.field), issue.message)
+            })
+        })
+        .collect();
+    if !issues.is_empty() {
+        return Err(CommandError::Validation { issues });
+    }
 
-    // if let Some(level) = levels.get_mut(0) {
-    //     // Assuming there's only one water plane and it's always the first one in the vec
-    //     if let Some(water_plane_data) = level.water_planes.get_mut(0) {
-    //         water_plane_data.config = config;
-    //     } else {
-    //         return Err("No water plane found in the project".to_string());
-    //     }
-    // } else {
-    //     return Err("No level found in the project".to_string());
-    // }
+    projects::save_saved_state(&project_id, &saved_state).await?;
 
-    // utilities::save_project_state(&project_id, &saved_state)
-    //     .await
-    //     .map_err(|e| format!("Failed to save project state: {}", e))?;
+    if let Some(extras) = extras {
+        extras::save_extras(&project_id, &extras)?;
+    }
 
     Ok(())
 }
 
+#[tauri::command]
+async fn get_project_extras(project_id: String) -> CommandResult<extras::ProjectExtras> {
+    println!("get_project_extras {:?}", project_id);
+
+    extras::load_extras(&project_id)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .manage(reqwest::Client::new())
-        .invoke_handler(tauri::generate_handler![list_projects, open_project_chat, log_message, get_chat_messages, send_message, configure_water_plane])
-        .register_uri_scheme_protocol("asset", move |app, request| {
-            // let path = request.uri().path()
-            //     .strip_prefix("asset://")
-            //     .unwrap_or_else(|| request.uri().path());
-            let path = request.uri().path().trim_start_matches('/');
-
-            // println!("asset path {:?}", path);
-
-            let decoded_path = match urlencoding::decode(path) {
-                Ok(p) => p.into_owned(),
-                Err(_) => {
-                    return Response::builder()
-                        .status(400)
-                        .header("Access-Control-Allow-Origin", "*")
-                        .body(Vec::new())
-                        .expect("Couldn't create response");
-                }
-            };
-
-            // println!("asset decoded_path {:?}", decoded_path);
-
-            if let Some(asset_dir) = get_common_os_dir() {
-                let asset_path = asset_dir.join(&decoded_path);
-
-                println!("asset asset_path {:?} {:?} {:?}", asset_path, asset_path.exists(), asset_path.is_file());
-
-                if asset_path.exists() && asset_path.is_file() {
-                    let mime_type = mime_guess::from_path(&asset_path).first_or_octet_stream();
-                    let content = fs::read(&asset_path).unwrap();
-
-                    Response::builder()
-                        .header("Content-Type", mime_type.to_string())
-                        .header("Access-Control-Allow-Origin", "*")
-                        .body(content)
-                        .expect("Couldn't create response")
-                } else {
-                    Response::builder()
-                        .status(404)
-                        .header("Access-Control-Allow-Origin", "*")
-                        .body(Vec::new())
-                        .expect("Couldn't create response")
-                }
-            } else {
-                Response::builder()
-                    .status(404)
-                    .header("Access-Control-Allow-Origin", "*")
-                    .body(Vec::new())
-                    .expect("Couldn't create response")
-            }
+        .setup(|app| {
+            let config_dir = app.path().app_config_dir()?;
+            app.manage(SettingsState::load(config_dir));
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![list_projects, create_project, rename_project, duplicate_project, delete_project, list_trash, restore_project, empty_trash, export_project_archive, import_project_archive, import_model, open_project_chat, log_message, get_chat_messages, publish_tool_catalog, send_message, save_project, get_project_extras, get_settings, update_settings])
+        .register_asynchronous_uri_scheme_protocol("asset", move |_app, request, responder| {
+            // file reads happen off the protocol thread
+            tauri::async_runtime::spawn(async move {
+                responder.respond(asset_protocol::handle_asset_request(request).await);
+            });
         })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
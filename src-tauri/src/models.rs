@@ -0,0 +1,161 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::archive::{asset_dir, safe_relative_path};
+use crate::error::{CommandError, CommandResult, ValidationIssue};
+
+pub const MODEL_EXTENSIONS: [&str; 2] = ["glb", "gltf"];
+const MODELS_DIR: &str = "models";
+
+/// A model in the asset directory, its asset id being its path there as served by `asset://`
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedModel {
+    pub asset_id: String,
+    pub name: String,
+    // every file belonging to the model, the model itself first
+    pub files: Vec<String>,
+}
+
+fn invalid(field: &str, message: impl Into<String>) -> CommandError {
+    CommandError::Validation {
+        issues: vec![ValidationIssue::new(field, message)],
+    }
+}
+
+pub(crate) fn is_model_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| MODEL_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// The JSON part of a `.gltf`, or the first chunk of a `.glb`
+pub(crate) fn gltf_json(bytes: &[u8]) -> Option<serde_json::Value> {
+    if bytes.starts_with(b"glTF") {
+        let length = u32::from_le_bytes(bytes.get(12..16)?.try_into().ok()?) as usize;
+        if bytes.get(16..20)? != b"JSON" {
+            return None;
+        }
+        return serde_json::from_slice(bytes.get(20..20 + length)?).ok();
+    }
+
+    serde_json::from_slice(bytes).ok()
+}
+
+/// Buffers and images a model loads from next to itself, embedded data URIs needing no copy
+pub(crate) fn external_files(gltf: &serde_json::Value) -> Vec<String> {
+    let mut uris: Vec<String> = ["buffers", "images"]
+        .iter()
+        .filter_map(|section| gltf.get(section).and_then(|v| v.as_array()))
+        .flatten()
+        .filter_map(|entry| entry.get("uri").and_then(|u| u.as_str()))
+        .filter(|uri| !uri.starts_with("data:"))
+        .map(|uri| urlencoding::decode(uri).map(|u| u.into_owned()).unwrap_or_else(|_| uri.to_string()))
+        .collect();
+    uris.sort();
+    uris.dedup();
+    uris
+}
+
+/// A folder under `models/` nothing else is using yet
+fn unused_model_dir(asset_dir: &Path, stem: &str) -> String {
+    let plain = format!("{}/{}", MODELS_DIR, stem);
+    if !asset_dir.join(&plain).exists() {
+        return plain;
+    }
+
+    let suffix = Uuid::new_v4().to_string();
+    format!("{}/{}-{}", MODELS_DIR, stem, &suffix[..8])
+}
+
+/// Copies a glTF or GLB and the files it references into the asset directory
+pub fn copy_model(source: &Path) -> CommandResult<ImportedModel> {
+    if !source.is_file() {
+        return Err(invalid("source", format!("{:?} isn't a file", source)));
+    }
+    if !is_model_file(source) {
+        return Err(invalid("source", format!("{:?} isn't a .glb or .gltf model", source)));
+    }
+
+    let bytes = fs::read(source).map_err(|e| CommandError::io("Failed to read model", e))?;
+    let gltf = gltf_json(&bytes).ok_or_else(|| CommandError::Decode {
+        message: format!("{:?} isn't a valid glTF model", source),
+    })?;
+
+    let source_dir = source.parent().map(Path::to_path_buf).unwrap_or_default();
+    let dependencies = external_files(&gltf);
+    let mut issues = Vec::new();
+    for dependency in &dependencies {
+        match safe_relative_path(dependency) {
+            Some(relative) if source_dir.join(&relative).is_file() => {}
+            Some(_) => issues.push(ValidationIssue::new("source", format!("{:?} is missing next to the model", dependency))),
+            None => issues.push(ValidationIssue::new("source", format!("{:?} points outside the model's folder", dependency))),
+        }
+    }
+    if !issues.is_empty() {
+        return Err(CommandError::Validation { issues });
+    }
+
+    let file_name = source.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let stem = source.file_stem().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "model".to_string());
+
+    let asset_dir = asset_dir()?;
+    let model_dir = unused_model_dir(&asset_dir, &stem);
+
+    let mut files = Vec::new();
+    for (relative, from) in std::iter::once((file_name.clone(), source.to_path_buf()))
+        .chain(dependencies.iter().map(|d| (d.clone(), source_dir.join(d))))
+    {
+        let asset = format!("{}/{}", model_dir, relative.replace('\\', "/"));
+        let target: PathBuf = asset_dir.join(&asset);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| CommandError::io("Failed to create model directory", e))?;
+        }
+        fs::copy(&from, &target).map_err(|e| CommandError::io(&format!("Failed to copy {:?}", from), e))?;
+        files.push(asset);
+    }
+
+    Ok(ImportedModel {
+        asset_id: files[0].clone(),
+        name: stem,
+        files,
+    })
+}
+
+/// A model already in the asset directory, reused without copying
+pub fn library_model(library_id: &str) -> CommandResult<ImportedModel> {
+    let relative = safe_relative_path(library_id)
+        .ok_or_else(|| invalid("libraryId", "must be a path inside the asset directory"))?;
+    let path = asset_dir()?.join(&relative);
+    if !path.is_file() || !is_model_file(&path) {
+        return Err(invalid("libraryId", format!("{:?} isn't a model in the asset directory", library_id)));
+    }
+
+    Ok(ImportedModel {
+        asset_id: library_id.replace('\\', "/"),
+        name: path.file_stem().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        files: vec![library_id.replace('\\', "/")],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_the_json_chunk_of_a_glb() {
+        let json = br#"{"asset":{"version":"2.0"}}"#;
+        let mut glb = b"glTF".to_vec();
+        glb.extend_from_slice(&2u32.to_le_bytes());
+        glb.extend_from_slice(&((20 + json.len()) as u32).to_le_bytes());
+        glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"JSON");
+        glb.extend_from_slice(json);
+
+        assert_eq!(gltf_json(&glb), Some(json!({ "asset": { "version": "2.0" } })));
+    }
+}
@@ -0,0 +1,160 @@
+use entropy_engine::helpers::utilities;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::error::{CommandError, CommandResult};
+use crate::projects::projects_dir;
+
+pub const INDEX_FILE_NAME: &str = "index.json";
+const THUMBNAIL_FILE_NAME: &str = "thumbnail.png";
+// loading a SavedState is the expensive part, so only a few run at once
+const MAX_CONCURRENT_LOADS: usize = 8;
+
+/// The small per-project manifest `list_projects` reads instead of the whole SavedState
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProjectIndexEntry {
+    pub id: String,
+    pub name: String,
+    // milliseconds since the epoch, the newest file in the project
+    pub modified: u64,
+    pub component_count: usize,
+    pub thumbnail: Option<String>,
+}
+
+fn modified_millis(path: &Path) -> Option<u64> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    modified.duration_since(UNIX_EPOCH).ok().map(|d| d.as_millis() as u64)
+}
+
+/// Newest mtime of anything in the project except the index itself
+fn project_modified(project_dir: &Path) -> u64 {
+    let mut newest = 0;
+    let mut stack = vec![project_dir.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else if entry.file_name() != INDEX_FILE_NAME {
+                newest = newest.max(modified_millis(&path).unwrap_or_default());
+            }
+        }
+    }
+
+    newest
+}
+
+fn read_index(project_dir: &Path, modified: u64) -> Option<ProjectIndexEntry> {
+    let contents = fs::read_to_string(project_dir.join(INDEX_FILE_NAME)).ok()?;
+    let entry: ProjectIndexEntry = serde_json::from_str(&contents).ok()?;
+
+    (entry.modified == modified).then_some(entry)
+}
+
+async fn rebuild_index(project_id: String, project_dir: PathBuf, modified: u64) -> CommandResult<ProjectIndexEntry> {
+    let saved_state = utilities::load_project_state(&project_id)
+        .await
+        .map_err(|e| CommandError::SavedStateCorrupt {
+            project_id: project_id.clone(),
+            message: e.to_string(),
+        })?;
+
+    let component_count = saved_state
+        .levels
+        .iter()
+        .flatten()
+        .filter_map(|level| level.components.as_ref())
+        .map(|components| components.len())
+        .sum();
+
+    let thumbnail = project_dir.join(THUMBNAIL_FILE_NAME);
+
+    let entry = ProjectIndexEntry {
+        id: project_id,
+        name: saved_state.project_name.clone(),
+        modified,
+        component_count,
+        thumbnail: thumbnail.is_file().then(|| thumbnail.to_string_lossy().to_string()),
+    };
+
+    // a stale or missing index only costs a reload next time, so write failures aren't fatal
+    match serde_json::to_string_pretty(&entry) {
+        Ok(contents) => {
+            if let Err(e) = fs::write(project_dir.join(INDEX_FILE_NAME), contents) {
+                eprintln!("Could not write project index for {}: {}", entry.id, e);
+            }
+        }
+        Err(e) => eprintln!("Could not serialize project index for {}: {}", entry.id, e),
+    }
+
+    Ok(entry)
+}
+
+/// Writes a fresh index entry for one project, for callers that just changed its files
+pub async fn refresh_index(project_id: &str) -> CommandResult<ProjectIndexEntry> {
+    let project_dir = projects_dir()?.join(project_id);
+    let modified = project_modified(&project_dir);
+
+    rebuild_index(project_id.to_string(), project_dir, modified).await
+}
+
+/// Reads every project's index entry, rebuilding the ones whose files changed since they were written
+pub async fn load_index() -> CommandResult<Vec<ProjectIndexEntry>> {
+    let projects_dir = projects_dir()?;
+
+    let mut entries = Vec::new();
+    let mut stale = Vec::new();
+
+    for entry in fs::read_dir(&projects_dir).map_err(|e| CommandError::io("Failed to read projects directory", e))? {
+        let entry = entry.map_err(|e| CommandError::io("Failed to read directory entry", e))?;
+        let path = entry.path();
+
+        if !path.is_dir() {
+            continue;
+        }
+
+        if let Some(project_id) = path.file_name().and_then(|s| s.to_str()) {
+            let modified = project_modified(&path);
+            match read_index(&path, modified) {
+                Some(index_entry) => entries.push(index_entry),
+                None => stale.push((project_id.to_string(), path.clone(), modified)),
+            }
+        }
+    }
+
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_LOADS));
+    let mut tasks = JoinSet::new();
+
+    for (project_id, project_dir, modified) in stale {
+        let semaphore = semaphore.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let result = rebuild_index(project_id.clone(), project_dir, modified).await;
+            (project_id, result)
+        });
+    }
+
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((_, Ok(index_entry))) => entries.push(index_entry),
+            Ok((project_id, Err(e))) => {
+                eprintln!("Could not load project state for project {}: {}", project_id, e);
+            }
+            Err(e) => eprintln!("Project index task failed: {}", e),
+        }
+    }
+
+    entries.sort_by(|a, b| b.modified.cmp(&a.modified));
+
+    Ok(entries)
+}
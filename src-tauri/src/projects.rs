@@ -0,0 +1,233 @@
+use entropy_engine::helpers::saved_data::SavedState;
+use entropy_engine::helpers::utilities;
+use entropy_engine::water_plane::config::WaterConfig;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+use crate::error::{CommandError, CommandResult};
+
+pub fn projects_dir() -> CommandResult<PathBuf> {
+    utilities::get_projects_dir()
+        .ok_or_else(|| CommandError::io("Failed to get projects directory", "no home directory"))
+}
+
+/// Loads a project's `SavedState`, telling a missing project apart from one that can't be read
+pub async fn load_saved_state(project_id: &str) -> CommandResult<SavedState> {
+    if !projects_dir()?.join(project_id).is_dir() {
+        return Err(CommandError::ProjectNotFound {
+            project_id: project_id.to_string(),
+        });
+    }
+
+    utilities::load_project_state(project_id)
+        .await
+        .map_err(|e| CommandError::SavedStateCorrupt {
+            project_id: project_id.to_string(),
+            message: e.to_string(),
+        })
+}
+
+pub async fn save_saved_state(project_id: &str, saved_state: &SavedState) -> CommandResult<()> {
+    utilities::save_project_state(project_id, saved_state)
+        .await
+        .map_err(|e| CommandError::io("Failed to save project state", e))?;
+
+    Ok(())
+}
+
+/// A fresh project: one level holding a landscape, a water plane, the player character and a light
+pub fn starter_saved_state(project_id: &str, project_name: &str) -> CommandResult<SavedState> {
+    let water_config = serde_json::to_value(WaterConfig::default())?;
+
+    let generic = |name: &str, position: [f32; 3]| {
+        json!({
+            "name": name,
+            "position": position,
+            "rotation": [0.0, 0.0, 0.0],
+            "scale": [1.0, 1.0, 1.0],
+        })
+    };
+
+    let components = json!([
+        {
+            "id": Uuid::new_v4().to_string(),
+            "kind": "Landscape",
+            "asset_id": "",
+            "generic_properties": generic("Landscape", [0.0, 0.0, 0.0]),
+            "landscape_properties": {},
+        },
+        {
+            "id": Uuid::new_v4().to_string(),
+            "kind": "WaterPlane",
+            "asset_id": "",
+            "generic_properties": generic("Water", [0.0, 2.0, 0.0]),
+            "water_properties": water_config,
+        },
+        {
+            "id": Uuid::new_v4().to_string(),
+            "kind": "PlayerCharacter",
+            "asset_id": "",
+            "generic_properties": generic("Player", [0.0, 5.0, 0.0]),
+            "player_properties": {},
+        },
+        {
+            "id": Uuid::new_v4().to_string(),
+            "kind": "PointLight",
+            "asset_id": "",
+            "generic_properties": generic("Light", [0.0, 20.0, 0.0]),
+            "light_properties": {
+                "intensity": 1.0,
+                "color": [1.0, 1.0, 1.0, 1.0],
+            },
+        },
+    ]);
+
+    let saved_state = json!({
+        "id": project_id,
+        "project_name": project_name,
+        "levels": [
+            {
+                "id": Uuid::new_v4().to_string(),
+                "components": components,
+            }
+        ],
+    });
+
+    serde_json::from_value(saved_state).map_err(|e| CommandError::SavedStateCorrupt {
+        project_id: project_id.to_string(),
+        message: format!("Starter project doesn't match SavedState: {}", e),
+    })
+}
+
+/// The same SavedState under another project id and name
+pub fn with_identity(saved_state: SavedState, project_id: &str, project_name: &str) -> CommandResult<SavedState> {
+    let mut value = serde_json::to_value(&saved_state)?;
+    if let Some(state) = value.as_object_mut() {
+        state.insert("id".to_string(), json!(project_id));
+        state.insert("project_name".to_string(), json!(project_name));
+    }
+
+    serde_json::from_value(value).map_err(|e| CommandError::SavedStateCorrupt {
+        project_id: project_id.to_string(),
+        message: e.to_string(),
+    })
+}
+
+const TRASH_METADATA_FILE: &str = "trashed.json";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TrashedProject {
+    pub id: String,
+    pub name: String,
+    pub deleted_at: u64,
+}
+
+/// Deleted projects are moved next to the projects directory so `list_projects` doesn't see them
+pub fn trash_dir() -> CommandResult<PathBuf> {
+    let projects_dir = projects_dir()?;
+    let parent = projects_dir.parent().unwrap_or(&projects_dir);
+    Ok(parent.join("trash"))
+}
+
+pub fn copy_dir_all(from: &Path, to: &Path) -> CommandResult<()> {
+    fs::create_dir_all(to).map_err(|e| CommandError::io("Failed to create directory", e))?;
+
+    for entry in fs::read_dir(from).map_err(|e| CommandError::io("Failed to read directory", e))? {
+        let entry = entry.map_err(|e| CommandError::io("Failed to read directory entry", e))?;
+        let target = to.join(entry.file_name());
+
+        if entry.path().is_dir() {
+            copy_dir_all(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target).map_err(|e| CommandError::io("Failed to copy file", e))?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn move_to_trash(project_id: &str, project_name: &str) -> CommandResult<()> {
+    let source = projects_dir()?.join(project_id);
+    if !source.is_dir() {
+        return Err(CommandError::ProjectNotFound {
+            project_id: project_id.to_string(),
+        });
+    }
+
+    let trash_dir = trash_dir()?;
+    fs::create_dir_all(&trash_dir).map_err(|e| CommandError::io("Failed to create trash directory", e))?;
+
+    let target = trash_dir.join(project_id);
+    if target.exists() {
+        fs::remove_dir_all(&target).map_err(|e| CommandError::io("Failed to replace trashed project", e))?;
+    }
+
+    fs::rename(&source, &target).map_err(|e| CommandError::io("Failed to move project to trash", e))?;
+
+    let metadata = TrashedProject {
+        id: project_id.to_string(),
+        name: project_name.to_string(),
+        deleted_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+    };
+    fs::write(target.join(TRASH_METADATA_FILE), serde_json::to_string_pretty(&metadata)?)
+        .map_err(|e| CommandError::io("Failed to write trash metadata", e))?;
+
+    Ok(())
+}
+
+pub fn list_trash() -> CommandResult<Vec<TrashedProject>> {
+    let trash_dir = trash_dir()?;
+    if !trash_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut trashed = Vec::new();
+    for entry in fs::read_dir(trash_dir).map_err(|e| CommandError::io("Failed to read trash directory", e))? {
+        let entry = entry.map_err(|e| CommandError::io("Failed to read directory entry", e))?;
+        let metadata_path = entry.path().join(TRASH_METADATA_FILE);
+
+        match fs::read_to_string(&metadata_path).map(|c| serde_json::from_str::<TrashedProject>(&c)) {
+            Ok(Ok(project)) => trashed.push(project),
+            _ => eprintln!("Skipping trash entry without metadata: {:?}", entry.path()),
+        }
+    }
+
+    trashed.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+
+    Ok(trashed)
+}
+
+pub fn restore_from_trash(project_id: &str) -> CommandResult<()> {
+    let source = trash_dir()?.join(project_id);
+    if !source.is_dir() {
+        return Err(CommandError::ProjectNotFound {
+            project_id: project_id.to_string(),
+        });
+    }
+
+    let target = projects_dir()?.join(project_id);
+    if target.exists() {
+        return Err(CommandError::io("Failed to restore project", "a project with the same id already exists"));
+    }
+
+    let _ = fs::remove_file(source.join(TRASH_METADATA_FILE));
+    fs::rename(&source, &target).map_err(|e| CommandError::io("Failed to restore project", e))?;
+
+    Ok(())
+}
+
+pub fn empty_trash() -> CommandResult<()> {
+    let trash_dir = trash_dir()?;
+    if trash_dir.is_dir() {
+        fs::remove_dir_all(&trash_dir).map_err(|e| CommandError::io("Failed to empty trash", e))?;
+    }
+
+    Ok(())
+}
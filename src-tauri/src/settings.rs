@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+use reqwest::Client;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use std::time::Duration;
+use std::fs;
+
+use crate::error::{CommandError, CommandResult};
+
+pub const DEFAULT_API_URL: &str = "http://localhost:3000";
+pub const API_URL_ENV_VAR: &str = "ENTROPY_API_URL";
+const SETTINGS_FILE_NAME: &str = "settings.json";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    pub api_url: String,
+    pub request_timeout_secs: u64,
+    // how many rounds of tool calls one user message may trigger
+    pub max_agent_steps: u32,
+    // keyed by local project id
+    pub project_overrides: HashMap<String, ProjectSettings>,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        AppSettings {
+            api_url: DEFAULT_API_URL.to_string(),
+            request_timeout_secs: 120,
+            max_agent_steps: 8,
+            project_overrides: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+}
+
+/// What the settings screen needs: the saved settings plus any environment override in effect
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SettingsView {
+    pub settings: AppSettings,
+    pub env_api_url: Option<String>,
+}
+
+/// Managed state holding the app settings and the `reqwest::Client`s built from them
+pub struct SettingsState {
+    config_path: PathBuf,
+    settings: RwLock<AppSettings>,
+    client: RwLock<Client>,
+    // for streamed responses, which may take far longer than the request timeout as a whole
+    stream_client: RwLock<Client>,
+}
+
+impl SettingsState {
+    pub fn load(config_dir: PathBuf) -> Self {
+        let config_path = config_dir.join(SETTINGS_FILE_NAME);
+
+        let settings = match fs::read_to_string(&config_path) {
+            Ok(contents) => serde_json::from_str::<AppSettings>(&contents).unwrap_or_else(|e| {
+                eprintln!("Could not parse settings at {:?}, using defaults: {}", config_path, e);
+                AppSettings::default()
+            }),
+            Err(_) => AppSettings::default(),
+        };
+
+        let client = build_client(&settings);
+        let stream_client = build_stream_client(&settings);
+
+        SettingsState {
+            config_path,
+            settings: RwLock::new(settings),
+            client: RwLock::new(client),
+            stream_client: RwLock::new(stream_client),
+        }
+    }
+
+    pub fn view(&self) -> SettingsView {
+        SettingsView {
+            settings: self.settings.read().expect("Settings lock poisoned").clone(),
+            env_api_url: env_api_url(),
+        }
+    }
+
+    /// `reqwest::Client` is reference counted, so handing out clones is cheap
+    pub fn client(&self) -> Client {
+        self.client.read().expect("Client lock poisoned").clone()
+    }
+
+    pub fn stream_client(&self) -> Client {
+        self.stream_client.read().expect("Client lock poisoned").clone()
+    }
+
+    /// Resolves the entropy-api base url: environment variable, then project override, then app setting
+    pub fn api_url(&self, project_id: Option<&str>) -> String {
+        if let Some(url) = env_api_url() {
+            return url;
+        }
+
+        let settings = self.settings.read().expect("Settings lock poisoned");
+
+        let project_url = project_id
+            .and_then(|id| settings.project_overrides.get(id))
+            .and_then(|o| o.api_url.clone());
+
+        project_url.unwrap_or_else(|| settings.api_url.clone())
+    }
+
+    pub fn update(&self, settings: AppSettings) -> CommandResult<SettingsView> {
+        let settings = normalize_settings(settings)?;
+
+        if let Some(parent) = self.config_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| CommandError::io("Failed to create config directory", e))?;
+        }
+
+        let contents = serde_json::to_string_pretty(&settings)?;
+        fs::write(&self.config_path, contents)
+            .map_err(|e| CommandError::io("Failed to write settings", e))?;
+
+        *self.client.write().expect("Client lock poisoned") = build_client(&settings);
+        *self.stream_client.write().expect("Client lock poisoned") = build_stream_client(&settings);
+        *self.settings.write().expect("Settings lock poisoned") = settings;
+
+        Ok(self.view())
+    }
+}
+
+fn env_api_url() -> Option<String> {
+    std::env::var(API_URL_ENV_VAR)
+        .ok()
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty())
+}
+
+fn build_client(settings: &AppSettings) -> Client {
+    Client::builder()
+        .timeout(Duration::from_secs(settings.request_timeout_secs.max(1)))
+        .build()
+        .unwrap_or_else(|e| {
+            eprintln!("Could not build configured client, falling back to defaults: {}", e);
+            Client::new()
+        })
+}
+
+/// Times out connecting or waiting between chunks, never a stream that keeps arriving
+fn build_stream_client(settings: &AppSettings) -> Client {
+    let timeout = Duration::from_secs(settings.request_timeout_secs.max(1));
+    Client::builder()
+        .connect_timeout(timeout)
+        .read_timeout(timeout)
+        .build()
+        .unwrap_or_else(|e| {
+            eprintln!("Could not build configured stream client, falling back to defaults: {}", e);
+            Client::new()
+        })
+}
+
+fn normalize_url(url: &str) -> CommandResult<String> {
+    let url = url.trim().trim_end_matches('/');
+
+    let parsed = reqwest::Url::parse(url).map_err(|e| CommandError::InvalidSettings {
+        message: format!("Invalid API url {:?}: {}", url, e),
+    })?;
+    if parsed.scheme() != "http" && parsed.scheme() != "https" {
+        return Err(CommandError::InvalidSettings {
+            message: format!("API url must be http or https: {:?}", url),
+        });
+    }
+
+    Ok(url.to_string())
+}
+
+fn normalize_settings(mut settings: AppSettings) -> CommandResult<AppSettings> {
+    settings.api_url = normalize_url(&settings.api_url)?;
+
+    if settings.max_agent_steps == 0 {
+        return Err(CommandError::InvalidSettings {
+            message: "Max tool steps must be at least 1".to_string(),
+        });
+    }
+
+    let mut overrides = HashMap::new();
+    for (project_id, mut project_settings) in settings.project_overrides.drain() {
+        project_settings.api_url = match project_settings.api_url.as_deref().map(str::trim) {
+            Some(url) if !url.is_empty() => Some(normalize_url(url)?),
+            _ => None,
+        };
+
+        // drop overrides which no longer override anything
+        if project_settings.api_url.is_some() {
+            overrides.insert(project_id, project_settings);
+        }
+    }
+    settings.project_overrides = overrides;
+
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_override(url: Option<&str>) -> AppSettings {
+        let mut settings = AppSettings::default();
+        settings.project_overrides.insert(
+            "project".to_string(),
+            ProjectSettings { api_url: url.map(str::to_string) },
+        );
+        settings
+    }
+
+    #[test]
+    fn trims_whitespace_and_trailing_slashes() {
+        let settings = AppSettings {
+            api_url: "  https://api.example.com//  ".to_string(),
+            ..AppSettings::default()
+        };
+
+        let settings = normalize_settings(settings).unwrap();
+        assert_eq!(settings.api_url, "https://api.example.com");
+    }
+
+    #[test]
+    fn rejects_unparseable_and_non_http_urls() {
+        for url in ["not a url", "ftp://example.com", "file:///etc/passwd", ""] {
+            let settings = AppSettings {
+                api_url: url.to_string(),
+                ..AppSettings::default()
+            };
+            assert!(
+                matches!(normalize_settings(settings), Err(CommandError::InvalidSettings { .. })),
+                "{:?} should be rejected",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_zero_agent_steps() {
+        let settings = AppSettings {
+            max_agent_steps: 0,
+            ..AppSettings::default()
+        };
+
+        assert!(matches!(normalize_settings(settings), Err(CommandError::InvalidSettings { .. })));
+    }
+
+    #[test]
+    fn normalizes_project_overrides() {
+        let settings = normalize_settings(with_override(Some(" http://localhost:4000/ "))).unwrap();
+        assert_eq!(
+            settings.project_overrides["project"].api_url.as_deref(),
+            Some("http://localhost:4000")
+        );
+
+        assert!(normalize_settings(with_override(Some("ftp://localhost"))).is_err());
+    }
+
+    #[test]
+    fn drops_overrides_without_a_url() {
+        for url in [None, Some(""), Some("   ")] {
+            let settings = normalize_settings(with_override(url)).unwrap();
+            assert!(settings.project_overrides.is_empty(), "{:?} should be dropped", url);
+        }
+    }
+}
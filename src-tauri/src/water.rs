@@ -0,0 +1,116 @@
+use entropy_engine::water_plane::config::WaterConfig;
+
+use crate::error::ValidationIssue;
+
+pub fn validate_water_config(config: &WaterConfig) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    let colors = [
+        ("shallow_color", config.shallow_color),
+        ("medium_color", config.medium_color),
+        ("deep_color", config.deep_color),
+    ];
+    for (name, color) in colors {
+        for (i, channel) in color.iter().enumerate() {
+            if !channel.is_finite() || !(0.0..=1.0).contains(channel) {
+                issues.push(ValidationIssue::new(format!("{}[{}]", name, i), "must be between 0 and 1"));
+            }
+        }
+    }
+
+    let non_negative = [
+        ("ripple_amplitude_multiplier", config.ripple_amplitude_multiplier),
+        ("ripple_freq", config.ripple_freq),
+        ("shoreline_foam_range", config.shoreline_foam_range),
+        ("sparkle_intensity", config.sparkle_intensity),
+        ("subsurface_multiplier", config.subsurface_multiplier),
+        ("fresnel_power", config.fresnel_power),
+        ("fresnel_multiplier", config.fresnel_multiplier),
+        ("wave1_amplitude", config.wave1_amplitude),
+        ("wave1_frequency", config.wave1_frequency),
+        ("wave2_amplitude", config.wave2_amplitude),
+        ("wave2_frequency", config.wave2_frequency),
+        ("wave3_amplitude", config.wave3_amplitude),
+        ("wave3_frequency", config.wave3_frequency),
+    ];
+    for (name, value) in non_negative {
+        if !value.is_finite() || value < 0.0 {
+            issues.push(ValidationIssue::new(name, "must be a non-negative number"));
+        }
+    }
+
+    let finite = [
+        ("ripple_speed", config.ripple_speed),
+        ("crest_foam_min", config.crest_foam_min),
+        ("crest_foam_max", config.crest_foam_max),
+        ("sparkle_threshold", config.sparkle_threshold),
+        ("wave1_speed", config.wave1_speed),
+        ("wave1_steepness", config.wave1_steepness),
+        ("wave2_speed", config.wave2_speed),
+        ("wave2_steepness", config.wave2_steepness),
+        ("wave3_speed", config.wave3_speed),
+        ("wave3_steepness", config.wave3_steepness),
+        ("wave1_direction[0]", config.wave1_direction[0]),
+        ("wave1_direction[1]", config.wave1_direction[1]),
+        ("wave2_direction[0]", config.wave2_direction[0]),
+        ("wave2_direction[1]", config.wave2_direction[1]),
+        ("wave3_direction[0]", config.wave3_direction[0]),
+        ("wave3_direction[1]", config.wave3_direction[1]),
+    ];
+    for (name, value) in finite {
+        if !value.is_finite() {
+            issues.push(ValidationIssue::new(name, "must be a finite number"));
+        }
+    }
+
+    if config.crest_foam_min > config.crest_foam_max {
+        issues.push(ValidationIssue::new("crest_foam_min", "must not be greater than crest_foam_max"));
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(issues: &[ValidationIssue]) -> Vec<&str> {
+        issues.iter().map(|i| i.field.as_str()).collect()
+    }
+
+    #[test]
+    fn default_config_is_valid() {
+        assert!(validate_water_config(&WaterConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn rejects_colors_outside_unit_range() {
+        let mut config = WaterConfig::default();
+        config.shallow_color[0] = 1.5;
+        config.deep_color[2] = f32::NAN;
+
+        let issues = validate_water_config(&config);
+        assert_eq!(fields(&issues), ["shallow_color[0]", "deep_color[2]"]);
+    }
+
+    #[test]
+    fn rejects_negative_and_non_finite_values() {
+        let mut config = WaterConfig::default();
+        config.wave1_amplitude = -1.0;
+        config.ripple_speed = f32::INFINITY;
+        config.wave2_direction[1] = f32::NAN;
+
+        let issues = validate_water_config(&config);
+        assert_eq!(fields(&issues), ["wave1_amplitude", "ripple_speed", "wave2_direction[1]"]);
+    }
+
+    #[test]
+    fn rejects_inverted_crest_foam_range() {
+        let mut config = WaterConfig::default();
+        config.crest_foam_min = 0.8;
+        config.crest_foam_max = 0.2;
+
+        let issues = validate_water_config(&config);
+        assert_eq!(fields(&issues), ["crest_foam_min"]);
+    }
+}
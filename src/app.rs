@@ -1,7 +1,6 @@
 use entropy_engine::core::pipeline::ExportPipeline;
 use entropy_engine::core::editor::WindowSize;
 use entropy_engine::helpers::load_project::place_project;
-use entropy_engine::helpers::saved_data::ComponentData;
 use entropy_engine::helpers::timelines::SavedTimelineStateConfig;
 use js_sys::Date;
 use leptos::html::Canvas;
@@ -10,7 +9,8 @@ use leptos::{prelude::*};
 use leptos_use::use_raf_fn;
 use leptos_use::utils::Pausable;
 use phosphor_leptos::{CHAT, CHATS, GAME_CONTROLLER, Icon, IconWeight, VIDEO};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::rc::Rc;
 use std::cell::RefCell;
 use uuid::Uuid;
@@ -18,18 +18,45 @@ use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use leptos::logging::log;
 use wasm_bindgen_futures::spawn_local as wasm_spawn_local;
-use entropy_engine::helpers::load_project::load_project;
 use leptos::web_sys;
 use entropy_engine::handlers::{EntropyPosition, handle_key_press, handle_mouse_move, handle_mouse_move_on_shift};
-use entropy_engine::water_plane::config::WaterConfig;
 use std::time::{Duration, SystemTime};
 
+use crate::components::camera_panel::CameraPanel;
 use crate::components::component_browser::ComponentPropertiesEditor;
+use crate::components::controls_panel::ControlsPanel;
+use crate::components::settings_panel::{SettingsPanel, SettingsView};
+use crate::components::tool_result_card::ToolFailureCard;
+use crate::errors::CommandError;
+use crate::extras::{GameAction, ProjectExtras};
+use crate::history::{self, EditScope, History};
+use crate::scene;
+use crate::tools::{self, ToolContext, ToolResult};
 
 #[wasm_bindgen]
 extern "C" {
-    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
-    async fn invoke(cmd: &str, args: JsValue) -> JsValue;
+    #[wasm_bindgen(catch, js_namespace = ["window", "__TAURI__", "core"])]
+    async fn invoke(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch, js_namespace = ["window", "__TAURI__", "event"])]
+    async fn listen(event: &str, handler: &Closure<dyn FnMut(JsValue)>) -> Result<JsValue, JsValue>;
+}
+
+const DEFAULT_MAX_AGENT_STEPS: u32 = 8;
+// long enough that a burst of tool calls ends up in a single write
+const AUTOSAVE_DELAY_MS: i32 = 2000;
+// how often a tool call waiting on another one checks again
+const TOOL_POLL_MS: i32 = 50;
+// a streamed tool call that hasn't finished by then is reported as failed
+const TOOL_RESULT_TIMEOUT_MS: i32 = 120_000;
+
+/// Invokes a Tauri command and decodes either its result or the `CommandError` it was rejected with
+pub async fn invoke_command<A: Serialize, T: DeserializeOwned>(cmd: &str, args: &A) -> Result<T, CommandError> {
+    let args = serde_wasm_bindgen::to_value(args)
+        .map_err(|e| CommandError::Decode { message: e.to_string() })?;
+    let value = invoke(cmd, args).await.map_err(CommandError::from_js)?;
+    serde_wasm_bindgen::from_value(value)
+        .map_err(|e| CommandError::Decode { message: e.to_string() })
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -40,11 +67,28 @@ pub struct Project {
     pub sessions: Vec<ChatSession>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ProjectInfo {
     pub id: String,
     pub name: String,
     pub path: String,
+    #[serde(default)]
+    pub modified: Option<u64>,
+    #[serde(default)]
+    pub component_count: Option<usize>,
+    #[serde(default)]
+    pub thumbnail: Option<String>,
+}
+
+impl ProjectInfo {
+    pub fn modified_label(&self) -> String {
+        match self.modified {
+            Some(millis) => Date::new(&JsValue::from_f64(millis as f64))
+                .to_locale_date_string("default", &JsValue::UNDEFINED)
+                .into(),
+            None => "N/A".to_string(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -86,225 +130,120 @@ pub struct OpenChatResponse {
     project: Project, session: ChatSession
 }
 
-async fn execute_tool_call(
-    tool_call: &ToolCall,
-    pipeline_store: LocalResource<Option<Rc<RefCell<ExportPipeline>>>>,
-) -> String {
-    log!("Executing tool call: {:?}", tool_call.function.name);
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrashedProject {
+    pub id: String,
+    pub name: String,
+    pub deleted_at: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeleteProjectResponse {
+    api_synced: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProjectAction {
+    Rename,
+    Duplicate,
+    Export,
+    Delete,
+}
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+/// Emitted by `send_message` while the assistant's response streams in
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ChatStreamEvent {
     #[serde(rename_all = "camelCase")]
-    struct TransformObjectArgs {
-        component_id: String,
-        translation: Option<[f32; 3]>,
-        rotation: Option<[f32; 3]>,
-        scale: Option<[f32; 3]>,
-    }
+    Delta { session_id: String, content: String },
+    #[serde(rename_all = "camelCase")]
+    ToolCallReady { session_id: String, tool_call: ToolCall },
+    #[serde(rename_all = "camelCase")]
+    Done { session_id: String, message: ChatMessage },
+}
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
-    // #[serde(rename_all = "camelCase")]
-    struct ConfigureWaterArgs {
-        shallow_color: Option<[f32; 3]>,
-        medium_color: Option<[f32; 3]>,
-        deep_color: Option<[f32; 3]>,
-        ripple_amplitude_multiplier: Option<f32>,
-        ripple_freq: Option<f32>,
-        ripple_speed: Option<f32>,
-        shoreline_foam_range: Option<f32>,
-        crest_foam_min: Option<f32>,
-        crest_foam_max: Option<f32>,
-        sparkle_intensity: Option<f32>,
-        sparkle_threshold: Option<f32>,
-        subsurface_multiplier: Option<f32>,
-        fresnel_power: Option<f32>,
-        fresnel_multiplier: Option<f32>,
-
-        // Wave 1 - primary wave
-        pub wave1_amplitude: Option<f32>,
-        pub wave1_frequency: Option<f32>,
-        pub wave1_speed: Option<f32>,
-        pub wave1_steepness: Option<f32>,
-        pub wave1_direction: Option<[f32; 2]>,
-
-        // Wave 2 - secondary wave
-        pub wave2_amplitude: Option<f32>,
-        pub wave2_frequency: Option<f32>,
-        pub wave2_speed: Option<f32>,
-        pub wave2_steepness: Option<f32>,
-        pub wave2_direction: Option<[f32; 2]>,
-
-        // Wave 3 - tertiary wave
-        pub wave3_amplitude: Option<f32>,
-        pub wave3_frequency: Option<f32>,
-        pub wave3_speed: Option<f32>,
-        pub wave3_steepness: Option<f32>,
-        pub wave3_direction: Option<[f32; 2]>,
+/// What the error banner's retry button should do again
+#[derive(Clone, Debug)]
+pub enum RetryAction {
+    OpenProject(ProjectInfo),
+    SendMessage(String),
+}
+
+async fn send_chat_message(
+    session_id: &str,
+    role: &str,
+    content: String,
+    tool_call_id: Option<String>,
+    project_id: &str,
+) -> Result<ChatMessage, CommandError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct SendMessageArgs {
+        session_id: String,
+        role: String,
+        content: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        tool_call_id: Option<String>,
+        project_id: String,
     }
 
-    if tool_call.function.name == "transformObject" {
-        let args: Result<TransformObjectArgs, _> = serde_json::from_str(&tool_call.function.arguments);
-        if let Ok(args) = args {
-            if let Some(pipeline_arc_val) = pipeline_store.get() {
-                if let Some(pipeline_arc) = pipeline_arc_val.as_ref() {
-                    let mut pipeline = pipeline_arc.borrow_mut();
-                    if let Some(editor) = pipeline.export_editor.as_mut() {
-                        // Update SavedState
-                        if let Some(saved_state) = editor.saved_state.as_mut() {
-                            if let Some(level) = saved_state.levels.as_mut().and_then(|l| l.get_mut(0)) {
-                                if let Some(components) = level.components.as_mut() {
-                                    if let Some(component) = components.iter_mut().find(|c| c.id == args.component_id) {
-                                        if let Some(translation) = args.translation {
-                                            component.generic_properties.position = translation;
-                                        }
-                                        if let Some(rotation) = args.rotation {
-                                            component.generic_properties.rotation = rotation;
-                                        }
-                                        if let Some(scale) = args.scale {
-                                            component.generic_properties.scale = scale;
-                                        }
-                                    }
-                                }
-                            }
-                        }
+    invoke_command("send_message", &SendMessageArgs {
+        session_id: session_id.to_string(),
+        role: role.to_string(),
+        content,
+        tool_call_id,
+        project_id: project_id.to_string(),
+    }).await
+}
 
-                        // Update RendererState
-                        if let Some(renderer_state) = editor.renderer_state.as_mut() {
-                            if let Some(model) = renderer_state.models.iter_mut().find(|m| m.id == args.component_id) {
-                                for mesh in model.meshes.iter_mut() {
-                                    if let Some(translation) = args.translation {
-                                        mesh.transform.update_position(translation);
-                                    }
-                                    if let Some(rotation) = args.rotation {
-                                        mesh.transform.update_rotation(rotation);
-                                    }
-                                    if let Some(scale) = args.scale {
-                                        mesh.transform.update_scale(scale);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+/// Tells entropy-api which tools this app can run for the session, so the model only calls ones that exist
+fn publish_tool_catalog(session_id: String, project_id: String) {
+    spawn_local(async move {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct PublishToolCatalogArgs {
+            session_id: String,
+            project_id: Option<String>,
+            tools: Vec<tools::ToolDefinition>,
         }
-    } else if tool_call.function.name == "configureWater" {
-        log!("Configuring water plane...");
-        let args: Result<ConfigureWaterArgs, _> = serde_json::from_str(&tool_call.function.arguments);
-        if let Ok(args) = args {
-            if let Some(pipeline_arc_val) = pipeline_store.get() {
-                if let Some(pipeline_arc) = pipeline_arc_val.as_ref() {
-                    let mut pipeline = pipeline_arc.borrow_mut();
-                    if let Some(editor) = pipeline.export_editor.as_mut() {
-                        if let Some(renderer_state) = editor.renderer_state.as_mut() {
-                            if let Some(water_plane) = renderer_state.water_planes.get_mut(0) {
-                                let mut current_config = water_plane.config; // Get current config
-
-                                log!("Configuring water plane still... {:?}", args);
-
-                                if let Some(color) = args.shallow_color {
-                                    current_config.shallow_color = [color[0], color[1], color[2], 1.0];
-                                }
-                                if let Some(color) = args.medium_color {
-                                    current_config.medium_color = [color[0], color[1], color[2], 1.0];
-                                }
-                                if let Some(color) = args.deep_color {
-                                    current_config.deep_color = [color[0], color[1], color[2], 1.0];
-                                }
-                                if let Some(val) = args.ripple_amplitude_multiplier {
-                                    current_config.ripple_amplitude_multiplier = val;
-                                }
-                                if let Some(val) = args.ripple_freq {
-                                    current_config.ripple_freq = val;
-                                }
-                                if let Some(val) = args.ripple_speed {
-                                    current_config.ripple_speed = val;
-                                }
-                                if let Some(val) = args.shoreline_foam_range {
-                                    current_config.shoreline_foam_range = val;
-                                }
-                                if let Some(val) = args.crest_foam_min {
-                                    current_config.crest_foam_min = val;
-                                }
-                                if let Some(val) = args.crest_foam_max {
-                                    current_config.crest_foam_max = val;
-                                }
-                                if let Some(val) = args.sparkle_intensity {
-                                    current_config.sparkle_intensity = val;
-                                }
-                                if let Some(val) = args.sparkle_threshold {
-                                    current_config.sparkle_threshold = val;
-                                }
-                                if let Some(val) = args.subsurface_multiplier {
-                                    current_config.subsurface_multiplier = val;
-                                }
-                                if let Some(val) = args.fresnel_power {
-                                    current_config.fresnel_power = val;
-                                }
-                                if let Some(val) = args.fresnel_multiplier {
-                                    current_config.fresnel_multiplier = val;
-                                }
 
-                                if let Some(val) = args.wave1_amplitude {
-                                    current_config.wave1_amplitude = val;
-                                }
-                                if let Some(val) = args.wave1_frequency {
-                                    current_config.wave1_frequency = val;
-                                }
-                                if let Some(val) = args.wave1_speed {
-                                    current_config.wave1_speed = val;
-                                }
-                                if let Some(val) = args.wave1_steepness {
-                                    current_config.wave1_steepness = val;
-                                }
-                                if let Some(val) = args.wave1_direction {
-                                    current_config.wave1_direction = val;
-                                }
-                                
-                                if let Some(val) = args.wave2_amplitude {
-                                    current_config.wave2_amplitude = val;
-                                }
-                                if let Some(val) = args.wave2_frequency {
-                                    current_config.wave2_frequency = val;
-                                }
-                                if let Some(val) = args.wave2_speed {
-                                    current_config.wave2_speed = val;
-                                }
-                                if let Some(val) = args.wave2_steepness {
-                                    current_config.wave2_steepness = val;
-                                }
-                                if let Some(val) = args.wave2_direction {
-                                    current_config.wave2_direction = val;
-                                }
+        let result: Result<(), CommandError> = invoke_command("publish_tool_catalog", &PublishToolCatalogArgs {
+            session_id,
+            project_id: Some(project_id),
+            tools: tools::registry().catalog(),
+        }).await;
 
-                                if let Some(val) = args.wave3_amplitude {
-                                    current_config.wave3_amplitude = val;
-                                }
-                                if let Some(val) = args.wave3_frequency {
-                                    current_config.wave3_frequency = val;
-                                }
-                                if let Some(val) = args.wave3_speed {
-                                    current_config.wave3_speed = val;
-                                }
-                                if let Some(val) = args.wave3_steepness {
-                                    current_config.wave3_steepness = val;
-                                }
-                                if let Some(val) = args.wave3_direction {
-                                    current_config.wave3_direction = val;
-                                }
+        if let Err(e) = result {
+            log!("Couldn't publish the tool catalog {:?}", e);
+        }
+    });
+}
 
-                                // water_plane.config = current_config;
-                                water_plane.update_config(&editor.gpu_resources.as_ref().expect("Couldn't get gpu resources").queue, current_config);
+/// Reads the project's `extras.json` into memory, where tools and saving pick it up
+fn load_project_extras(project_id: String, extras: RwSignal<ProjectExtras>) {
+    extras.set(ProjectExtras::default());
+    spawn_local(async move {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct GetProjectExtrasArgs {
+            project_id: String,
+        }
 
-                                log!("Water plane configured {:?}", water_plane.config);
-                            }
-                        }
-                    }
-                }
-            }
+        match invoke_command::<_, ProjectExtras>("get_project_extras", &GetProjectExtrasArgs { project_id }).await {
+            Ok(loaded) => extras.set(loaded),
+            Err(e) => log!("Couldn't load project extras {:?}", e),
         }
-    }
+    });
+}
 
-    "{\"success\": true}".to_string()
+/// Resolves after `ms` milliseconds
+async fn sleep(ms: i32) {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        let _ = web_sys::window()
+            .expect("Couldn't get window")
+            .set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, ms);
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
 }
 
 #[component]
@@ -313,6 +252,7 @@ pub fn ProjectCanvas(
     pipeline_store: LocalResource<Option<Rc<RefCell<ExportPipeline>>>>,
     is_initialized: ReadSignal<bool>,
     set_is_initialized: WriteSignal<bool>,
+    extras: RwSignal<ProjectExtras>,
 ) -> impl IntoView {
     let canvas_ref = NodeRef::<Canvas>::new();
     
@@ -352,13 +292,15 @@ pub fn ProjectCanvas(
                             )
                             .await;
 
+                        drop(pipeline_guard);
+
                         log!("loading project...");
 
-                        let editor = pipeline_guard.export_editor.as_mut().expect("Couldn't get editor");
-                        load_project(editor, &project_id).await;
+                        scene::place_scene(&pipeline_arc_clone, &project_id).await;
 
                         log!("configuring surface...");
 
+                        let pipeline_guard = pipeline_arc_clone.borrow();
                         let editor = pipeline_guard.export_editor.as_ref().expect("Couldn't get editor");
                         let camera = editor.camera.as_ref().expect("Couldn't get camera");
                         let gpu_resources = pipeline_guard.gpu_resources.as_ref().expect("Couldn't get gpu resources");
@@ -392,7 +334,14 @@ pub fn ProjectCanvas(
         if is_initialized.get() {
             if let Some(pipeline) = pipeline_store.get_untracked() {
                 if let Some(pipeline_arc) = pipeline.as_ref() {
-                    let mut pipeline = pipeline_arc.borrow_mut();
+                    // a scene reload holds the pipeline across an await, skip until it's done
+                    let Ok(mut pipeline) = pipeline_arc.try_borrow_mut() else {
+                        return;
+                    };
+                    // a scene reload has the editor out until the project is placed again
+                    if pipeline.export_editor.is_none() {
+                        return;
+                    }
                     let gpu_resources = match pipeline.gpu_resources.as_ref() {
                         Some(res) => res.clone(),
                         None => return,
@@ -433,10 +382,15 @@ pub fn ProjectCanvas(
                 node_ref=canvas_ref 
                 tabindex="0"
                 on:keydown=move |ev: web_sys::KeyboardEvent| {
-                    let key = ev.key();
+                    // the project's input map decides what the engine sees
+                    let Some(key) = extras.with_untracked(|e| e.controls.engine_key(&ev.key())) else {
+                        return;
+                    };
                     if let Some(pipeline_store_val) = pipeline_store.get() {
                         if let Some(pipeline_arc) = pipeline_store_val.as_ref() {
-                            let mut pipeline = pipeline_arc.borrow_mut();
+                            let Ok(mut pipeline) = pipeline_arc.try_borrow_mut() else {
+                                return;
+                            };
                             if let Some(editor) = pipeline.export_editor.as_mut() {
                                 let camera = editor.camera.as_ref().expect("Couldn't get camera");
 
@@ -447,11 +401,29 @@ pub fn ProjectCanvas(
                         }
                     }
                 }
+                on:mousedown=move |ev: web_sys::MouseEvent| {
+                    // only mouse buttons bound to an action reach the engine, as the key of that action
+                    let Some(key) = extras.with_untracked(|e| e.controls.engine_key(&format!("Mouse{}", ev.button()))) else {
+                        return;
+                    };
+                    if let Some(pipeline_store_val) = pipeline_store.get() {
+                        if let Some(pipeline_arc) = pipeline_store_val.as_ref() {
+                            let Ok(mut pipeline) = pipeline_arc.try_borrow_mut() else {
+                                return;
+                            };
+                            if let Some(editor) = pipeline.export_editor.as_mut() {
+                                handle_key_press(editor, key.as_str(), true);
+                            }
+                        }
+                    }
+                }
                 on:mousemove=move |ev: web_sys::MouseEvent| {
                     
                         if let Some(pipeline_store_val) = pipeline_store.get() {
                             if let Some(pipeline_arc) = pipeline_store_val.as_ref() {
-                                let mut pipeline = pipeline_arc.borrow_mut();
+                                let Ok(mut pipeline) = pipeline_arc.try_borrow_mut() else {
+                                    return;
+                                };
                                 if let Some(editor) = pipeline.export_editor.as_mut() {
                                     let canv = canvas_ref.get();
                                     let canv = canv.as_ref().expect("Couldn't get canvas ref");
@@ -475,7 +447,16 @@ pub fn ProjectCanvas(
                                         editor
                                     );
                                     
-                                    if ev.shift_key() {
+                                    let looking = extras.with_untracked(|e| {
+                                        e.controls.is_held(
+                                            GameAction::CameraLook,
+                                            ev.shift_key(),
+                                            ev.ctrl_key(),
+                                            ev.alt_key(),
+                                            ev.buttons(),
+                                        )
+                                    });
+                                    if looking {
                                         handle_mouse_move_on_shift(dx, dy, editor);
                                     }
                                 }
@@ -491,25 +472,46 @@ pub fn ProjectCanvas(
 #[component]
 pub fn App() -> impl IntoView {
     let (show_chat, set_show_chat) = signal(false);
+    let (show_settings, set_show_settings) = signal(false);
+    let (show_new_project, set_show_new_project) = signal(false);
+    let (new_project_name, set_new_project_name) = signal(String::new());
     let (selected_project, set_selected_project) = signal::<Option<ProjectInfo>>(None);
     let (current_session, set_current_session) = signal::<Option<ChatSession>>(None);
     let (refetch_projects, set_refetch_projects) = signal(false);
+    let (refetch_trash, set_refetch_trash) = signal(false);
+    let (context_menu, set_context_menu) = signal::<Option<(ProjectInfo, i32, i32)>>(None);
     let (refetch_messages, set_refetch_messages) = signal(false);
     let (is_initialized, set_is_initialized) = signal(false);
     let (message_content, set_message_content) = signal(String::new());
     let (local_messages, set_local_messages) = signal(Vec::<ChatMessage>::new());
+    let (streaming_content, set_streaming_content) = signal::<Option<String>>(None);
+    // tool call id to its result, `None` while it is still running
+    let executed_tool_calls = StoredValue::new(std::collections::HashMap::<String, Option<String>>::new());
+    // tools edit the same editor, so they run one at a time in the order they arrived: (next ticket, ticket running)
+    let tool_tickets = StoredValue::new((0u64, 0u64));
+    let (state_revision, set_state_revision) = signal(0u32);
+    let history = RwSignal::new(History::default());
+    let extras = RwSignal::new(ProjectExtras::default());
+    let (command_error, set_command_error) = signal::<Option<(CommandError, Option<RetryAction>)>>(None);
     let input_ref: NodeRef<leptos::html::Input> = NodeRef::new();
 
     // DO NOT use "create_resource" as the leptos_reactive crate is deprecated, LocalResource is the recommended way for a client-side Tauri + Leptos app
-    let projects_resource: LocalResource<Result<Vec<ProjectInfo>, String>> = LocalResource::new(
+    let projects_resource: LocalResource<Result<Vec<ProjectInfo>, CommandError>> = LocalResource::new(
         // move || refetch_projects.get(),
         move || async move {
             if refetch_projects.get() {
                 set_refetch_projects.update_untracked(|val| *val = false);
             }
-            let args = serde_wasm_bindgen::to_value(&()).unwrap();
-            let projects_js_value = invoke("list_projects", args).await;
-            serde_wasm_bindgen::from_value(projects_js_value).map_err(|e| e.to_string())
+            invoke_command("list_projects", &()).await
+        },
+    );
+
+    let trash_resource: LocalResource<Result<Vec<TrashedProject>, CommandError>> = LocalResource::new(
+        move || async move {
+            if refetch_trash.get() {
+                set_refetch_trash.update_untracked(|val| *val = false);
+            }
+            invoke_command("list_trash", &()).await
         },
     );
 
@@ -520,7 +522,7 @@ pub fn App() -> impl IntoView {
         },
     );
 
-    let messages_resource: LocalResource<std::result::Result<Vec<ChatMessage>, String>> = LocalResource::new(
+    let messages_resource: LocalResource<std::result::Result<Vec<ChatMessage>, CommandError>> = LocalResource::new(
     move || async move { 
             if refetch_messages.get() {
                 set_refetch_messages.update_untracked(|val| *val = false);
@@ -531,11 +533,12 @@ pub fn App() -> impl IntoView {
                 #[serde(rename_all = "camelCase")]
                 struct GetChatMessagesArgs {
                     session_id: String,
+                    project_id: Option<String>,
                 }
-                let args = serde_wasm_bindgen::to_value(&GetChatMessagesArgs { session_id }).unwrap();
-                let messages = invoke("get_chat_messages", args).await;
-                let mut remote: Vec<ChatMessage> = serde_wasm_bindgen::from_value(messages)
-                    .map_err(|e| e.to_string())?;
+                let mut remote: Vec<ChatMessage> = invoke_command("get_chat_messages", &GetChatMessagesArgs {
+                    session_id,
+                    project_id: selected_project.get_untracked().map(|p| p.id),
+                }).await?;
                 
                 // Combine with local messages here
                 remote.extend(local_messages.get_untracked().iter().cloned());
@@ -555,99 +558,555 @@ pub fn App() -> impl IntoView {
                 project_path: String,
             }
 
-            let args = serde_wasm_bindgen::to_value(&OpenProjectChatArgs {
+            let result: Result<OpenChatResponse, CommandError> = invoke_command("open_project_chat", &OpenProjectChatArgs {
                 project_name: project.name.clone(),
                 project_path: project.path.clone(),
-            })
-            .unwrap();
-
-            let result = invoke("open_project_chat", args).await;
+            }).await;
 
-            if !result.is_null() && !result.is_undefined() {
-                let result: Result<OpenChatResponse, serde_wasm_bindgen::Error> =
-                    serde_wasm_bindgen::from_value(result);
-                if let Ok(res) = result {
+            match result {
+                Ok(res) => {
                     let p = res.project;
 
                     log!("Setting up chat {:?} {:?}", p.id, res.session.id);
 
+                    set_command_error.set(None);
+
+                    let project_id = project.id.clone();
+
                     // Use untracked() to access signals safely in async
                     set_selected_project.update(|val| {
                         *val = Some(ProjectInfo {
-                            id: project.id, // this is the local id
+                            // id stays the local id
                             // apiProjectId: p.id,
                             name: p.name,
                             path: p.path,
+                            ..project
                         });
                     });
+                    publish_tool_catalog(res.session.id.clone(), project_id.clone());
                     set_current_session.update(|val| *val = Some(res.session));
+                    // snapshots from another project can't be applied to this one
+                    history.set(History::default());
+                    load_project_extras(project_id, extras);
                     set_show_chat.update(|val| *val = true);
-                } else {
-                    log!("Couldn't decode chat result 2");
                 }
-            } else {
-                log!("Couldn't decode chat result 1");
+                Err(e) => {
+                    log!("Couldn't open project chat {:?}", e);
+                    set_command_error.set(Some((e, Some(RetryAction::OpenProject(project)))));
+                }
             }
         });
     };
 
-    let send_message = move |pipeline_store: LocalResource<Option<Rc<RefCell<ExportPipeline>>>>| {
+    let create_project = move |_| {
+        let project_name = new_project_name.get_untracked();
+        spawn_local(async move {
+            #[derive(Serialize)]
+            #[serde(rename_all = "camelCase")]
+            struct CreateProjectArgs {
+                project_name: String,
+            }
+
+            let result: Result<ProjectInfo, CommandError> =
+                invoke_command("create_project", &CreateProjectArgs { project_name }).await;
+
+            match result {
+                Ok(project) => {
+                    log!("Created project {:?}", project.id);
+                    set_show_new_project.set(false);
+                    set_new_project_name.set(String::new());
+                    set_refetch_projects.set(true);
+                    open_project_chat(project);
+                }
+                Err(e) => {
+                    log!("Couldn't create project {:?}", e);
+                    set_command_error.set(Some((e, None)));
+                }
+            }
+        });
+    };
+
+    let run_project_action = move |action: ProjectAction, project: ProjectInfo| {
+        set_context_menu.set(None);
+
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ProjectIdArgs {
+            project_id: String,
+        }
+
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct RenameProjectArgs {
+            project_id: String,
+            project_name: String,
+        }
+
+        let result_args = match action {
+            ProjectAction::Rename => {
+                let new_name = window()
+                    .prompt_with_message_and_default("Rename project", &project.name)
+                    .ok()
+                    .flatten();
+                match new_name {
+                    Some(name) if !name.trim().is_empty() => Some(name),
+                    _ => return,
+                }
+            }
+            ProjectAction::Delete => {
+                let confirmed = window()
+                    .confirm_with_message(&format!("Move \"{}\" to the trash?", project.name))
+                    .unwrap_or(false);
+                if !confirmed {
+                    return;
+                }
+                None
+            }
+            ProjectAction::Duplicate | ProjectAction::Export => None,
+        };
+
+        spawn_local(async move {
+            let result: Result<(), CommandError> = match action {
+                ProjectAction::Rename => invoke_command::<_, ProjectInfo>("rename_project", &RenameProjectArgs {
+                    project_id: project.id.clone(),
+                    project_name: result_args.unwrap_or_default(),
+                }).await.map(|_| ()),
+                ProjectAction::Duplicate => invoke_command::<_, ProjectInfo>("duplicate_project", &ProjectIdArgs {
+                    project_id: project.id.clone(),
+                }).await.map(|_| ()),
+                ProjectAction::Export => invoke_command::<_, Option<String>>("export_project_archive", &ProjectIdArgs {
+                    project_id: project.id.clone(),
+                }).await.map(|path| {
+                    if let Some(path) = path {
+                        log!("Exported project to {:?}", path);
+                    }
+                }),
+                ProjectAction::Delete => invoke_command::<_, DeleteProjectResponse>("delete_project", &ProjectIdArgs {
+                    project_id: project.id.clone(),
+                }).await.map(|res| {
+                    if !res.api_synced {
+                        log!("Project {:?} was deleted locally but entropy-api couldn't be updated", project.id);
+                    }
+                }),
+            };
+
+            if let Err(e) = result {
+                log!("Project action {:?} failed {:?}", action, e);
+                set_command_error.set(Some((e, None)));
+            }
+
+            set_refetch_projects.set(true);
+            set_refetch_trash.set(true);
+        });
+    };
+
+    let import_project = move |_| {
+        spawn_local(async move {
+            let result: Result<Option<ProjectInfo>, CommandError> =
+                invoke_command("import_project_archive", &()).await;
+
+            match result {
+                Ok(Some(project)) => {
+                    log!("Imported project {:?}", project.id);
+                    set_refetch_projects.set(true);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    log!("Couldn't import project {:?}", e);
+                    set_command_error.set(Some((e, None)));
+                }
+            }
+        });
+    };
+
+    let restore_project = move |project_id: String| {
+        spawn_local(async move {
+            #[derive(Serialize)]
+            #[serde(rename_all = "camelCase")]
+            struct RestoreProjectArgs {
+                project_id: String,
+            }
+
+            let result: Result<ProjectInfo, CommandError> =
+                invoke_command("restore_project", &RestoreProjectArgs { project_id }).await;
+            if let Err(e) = result {
+                set_command_error.set(Some((e, None)));
+            }
+
+            set_refetch_projects.set(true);
+            set_refetch_trash.set(true);
+        });
+    };
+
+    let empty_trash = move |_| {
+        let confirmed = window()
+            .confirm_with_message("Permanently delete everything in the trash?")
+            .unwrap_or(false);
+        if !confirmed {
+            return;
+        }
+
+        spawn_local(async move {
+            if let Err(e) = invoke_command::<_, ()>("empty_trash", &()).await {
+                set_command_error.set(Some((e, None)));
+            }
+            set_refetch_trash.set(true);
+        });
+    };
+
+    let failed_tool_result = |tool_call: &ToolCall, message: &str| {
+        serde_json::to_string(&ToolResult {
+            success: false,
+            tool: tool_call.function.name.clone(),
+            result: None,
+            error: Some(tools::ToolError::failed(message)),
+        })
+        .unwrap_or_default()
+    };
+
+    let execute_tool_call = move |tool_call: ToolCall| async move {
+        let Some(project) = selected_project.get_untracked() else {
+            return failed_tool_result(&tool_call, "No project is open");
+        };
+
+        set_local_messages.update(|messages| {
+            messages.push(ChatMessage {
+                id: Uuid::new_v4().to_string(),
+                role: "system".to_string(),
+                content: Some("Implementing changes... ".to_string() + &tool_call.function.name + " " + &tool_call.function.arguments),
+                tool_call_id: None,
+                tool_calls: None,
+            });
+        });
+
+        let ctx = ToolContext {
+            pipeline_store,
+            project_id: project.id.clone(),
+            set_state_revision,
+            history,
+            extras,
+        };
+        tools::registry().execute(&tool_call, &ctx).await
+    };
+
+    // streamed tool calls run as soon as their arguments are complete, so the final message must not run them again
+    let run_tool_call = move |tool_call: ToolCall| async move {
+        if executed_tool_calls.with_value(|results| results.contains_key(&tool_call.id)) {
+            // started from the stream, wait for it to finish
+            let mut waited = 0;
+            loop {
+                if let Some(Some(result)) = executed_tool_calls.with_value(|results| results.get(&tool_call.id).cloned()) {
+                    return result;
+                }
+                if waited >= TOOL_RESULT_TIMEOUT_MS {
+                    log!("Gave up waiting for tool call {:?}", tool_call.id);
+                    return failed_tool_result(&tool_call, "Timed out waiting for the tool to finish");
+                }
+                sleep(TOOL_POLL_MS).await;
+                waited += TOOL_POLL_MS;
+            }
+        }
+        executed_tool_calls.update_value(|results| {
+            results.insert(tool_call.id.clone(), None);
+        });
+
+        let ticket = tool_tickets
+            .try_update_value(|(next, _)| {
+                *next += 1;
+                *next - 1
+            })
+            .unwrap_or_default();
+        while tool_tickets.with_value(|(_, running)| *running != ticket) {
+            sleep(TOOL_POLL_MS).await;
+        }
+
+        let result = execute_tool_call(tool_call.clone()).await;
+        executed_tool_calls.update_value(|results| {
+            results.insert(tool_call.id.clone(), Some(result.clone()));
+        });
+        tool_tickets.update_value(|(_, running)| *running += 1);
+
+        result
+    };
+
+    spawn_local(async move {
+        let handler = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            let payload = js_sys::Reflect::get(&event, &JsValue::from_str("payload")).unwrap_or(JsValue::UNDEFINED);
+            let stream_event: ChatStreamEvent = match serde_wasm_bindgen::from_value(payload) {
+                Ok(stream_event) => stream_event,
+                Err(e) => {
+                    log!("Couldn't decode chat stream event {:?}", e);
+                    return;
+                }
+            };
+
+            let current_session_id = current_session.get_untracked().map(|s| s.id);
+            match stream_event {
+                ChatStreamEvent::Delta { session_id, content } if Some(&session_id) == current_session_id.as_ref() => {
+                    set_streaming_content.update(|streaming| streaming.get_or_insert_with(String::new).push_str(&content));
+                }
+                ChatStreamEvent::ToolCallReady { session_id, tool_call } if Some(&session_id) == current_session_id.as_ref() => {
+                    spawn_local(async move {
+                        run_tool_call(tool_call).await;
+                    });
+                }
+                ChatStreamEvent::Done { session_id, .. } if Some(&session_id) == current_session_id.as_ref() => {
+                    set_streaming_content.set(None);
+                }
+                _ => {}
+            }
+        });
+
+        if let Err(e) = listen("chat-stream", &handler).await {
+            log!("Couldn't listen for chat stream events {:?}", e);
+        }
+
+        // the listener lives as long as the app
+        handler.forget();
+    });
+
+    let edit_scope = EditScope {
+        pipeline_store,
+        selected_project,
+        set_state_revision,
+        history,
+        extras,
+    };
+
+    let (is_saving, set_is_saving) = signal(false);
+
+    // writes the editor's saved state to disk, resolving to whether it worked
+    let save_project = move || async move {
+        let Some(ctx) = edit_scope.context() else {
+            return false;
+        };
+        let revision = history.with_untracked(|h| h.revision());
+
+        set_is_saving.set(true);
+        let result = scene::save_scene(&ctx).await;
+        set_is_saving.set(false);
+
+        match result {
+            Ok(()) => {
+                history.update(|h| h.mark_saved(revision));
+                true
+            }
+            Err(e) => {
+                log!("Couldn't save project {:?}", e);
+                set_command_error.set(Some((e, None)));
+                false
+            }
+        }
+    };
+
+    // autosave once edits have settled for a moment
+    create_effect(move |_| {
+        let revision = history.with(|h| h.revision());
+        if !history.with_untracked(|h| h.is_dirty()) {
+            return;
+        }
+
+        spawn_local(async move {
+            sleep(AUTOSAVE_DELAY_MS).await;
+            let settled = history.with_untracked(|h| h.revision() == revision && h.is_dirty());
+            if settled && !is_saving.get_untracked() {
+                save_project().await;
+            }
+        });
+    });
+
+    // the engine only places what `SavedState` holds, so the extras go on top after every placement and edit
+    create_effect(move |_| {
+        state_revision.track();
+        if !is_initialized.get() {
+            return;
+        }
+        let Some(pipeline_arc) = edit_scope.context().and_then(|ctx| ctx.pipeline()) else {
+            return;
+        };
+        extras.with(|extras| scene::apply_extras(&pipeline_arc, extras));
+    });
+
+    // shown instead of closing while the project has unsaved changes
+    let (close_prompt, set_close_prompt) = signal(false);
+
+    let close_chat = move || {
+        if !history.with_untracked(|h| h.is_dirty()) {
+            set_show_chat.set(false);
+            return;
+        }
+
+        set_close_prompt.set(true);
+    };
+
+    let save_and_close = move || {
+        spawn_local(async move {
+            if save_project().await {
+                set_close_prompt.set(false);
+                set_show_chat.set(false);
+            }
+        });
+    };
+
+    // puts the scene and extras back as they are on disk, dropping the undo history with the edits
+    let discard_and_close = move || {
+        let Some(ctx) = edit_scope.context() else {
+            set_close_prompt.set(false);
+            set_show_chat.set(false);
+            return;
+        };
+        set_close_prompt.set(false);
+        set_show_chat.set(false);
+
+        history.set(History::default());
+        load_project_extras(ctx.project_id.clone(), extras);
+        spawn_local(async move {
+            if let Some(pipeline_arc) = ctx.pipeline() {
+                scene::place_scene(&pipeline_arc, &ctx.project_id).await;
+            }
+            ctx.set_state_revision.update(|revision| *revision += 1);
+        });
+    };
+
+    let undo = move || {
+        if let Some(ctx) = edit_scope.context() {
+            spawn_local(async move { history::undo(&ctx).await });
+        }
+    };
+
+    let redo = move || {
+        if let Some(ctx) = edit_scope.context() {
+            spawn_local(async move { history::redo(&ctx).await });
+        }
+    };
+
+    // picks a model file and places it at the origin, as one undoable step
+    let import_model = move || {
+        let Some(ctx) = edit_scope.context() else {
+            return;
+        };
+        spawn_local(async move {
+            let before = history::snapshot(&ctx);
+            match tools::import_model(&ctx, None, None, Some([0.0, 0.0, 0.0]), None).await {
+                Ok(Some((model, _))) => log!("Imported model {:?}", model.asset_id),
+                Ok(None) => {}
+                Err(e) => log!("Couldn't import model {:?}", e),
+            }
+            history::record(&ctx, "Import model", &before, &history::snapshot(&ctx));
+        });
+    };
+
+    let _history_keys = window_event_listener(leptos::ev::keydown, move |ev: web_sys::KeyboardEvent| {
+        if !(ev.ctrl_key() || ev.meta_key()) {
+            return;
+        }
+
+        // text fields keep their own undo
+        let in_text_field = ev
+            .target()
+            .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+            .map(|el| matches!(el.tag_name().as_str(), "INPUT" | "TEXTAREA"))
+            .unwrap_or(false);
+        if in_text_field {
+            return;
+        }
+
+        match ev.key().to_lowercase().as_str() {
+            "z" if ev.shift_key() => {
+                ev.prevent_default();
+                redo();
+            }
+            "z" => {
+                ev.prevent_default();
+                undo();
+            }
+            "y" => {
+                ev.prevent_default();
+                redo();
+            }
+            _ => {}
+        }
+    });
+
+    let send_content = move |content: String, pipeline_store: LocalResource<Option<Rc<RefCell<ExportPipeline>>>>| {
         if let Some(session) = current_session.get() {
-            let content = message_content.get(); // Get value before spawn
             set_local_messages.set(Vec::new());
             spawn_local(async move {
-                #[derive(Serialize)]
-                #[serde(rename_all = "camelCase")]
-                struct SendMessageArgs {
-                    session_id: String,
-                    role: String,
-                    content: String,
-                    #[serde(skip_serializing_if = "Option::is_none")]
-                    tool_call_id: Option<String>,
-                    project_id: String,
-                }
-
-                let args = serde_wasm_bindgen::to_value(&SendMessageArgs {
-                    session_id: session.id.clone(),
-                    role: "user".to_string(),
-                    content,
-                    tool_call_id: None,
-                    project_id: selected_project.get().as_ref().expect("Couldn't get selected project").id.clone()
-                })
-                .unwrap();
+                let Some(project) = selected_project.get_untracked() else {
+                    return;
+                };
 
                 set_message_content.update(|val| *val = String::new());
                 if let Some(input) = input_ref.get_untracked() {
                     input.set_value("");
                 }
 
-                let response_js_value = invoke("send_message", args).await;
-                let response: Result<ChatMessage, _> = serde_wasm_bindgen::from_value(response_js_value);
+                let max_steps = invoke_command::<_, SettingsView>("get_settings", &())
+                    .await
+                    .map(|view| view.settings.max_agent_steps)
+                    .unwrap_or(DEFAULT_MAX_AGENT_STEPS);
+
+                // the backend sends the saved state along, so pending edits have to be on disk first
+                if history.with_untracked(|h| h.is_dirty()) {
+                    save_project().await;
+                }
+
+                let mut response = send_chat_message(&session.id, "user", content.clone(), None, &project.id).await;
+                set_streaming_content.set(None);
 
-                if let Ok(message) = response {
-                    log!("Response okay");
+                if let Err(e) = &response {
+                    log!("Couldn't send message {:?}", e);
+                    set_command_error.set(Some((e.clone(), Some(RetryAction::SendMessage(content)))));
+                    return;
+                }
 
-                    if let Some(tool_calls) = message.tool_calls {
-                        log!("Tool calls...");
+                set_command_error.set(None);
 
-                        let tool_calls_data = tool_calls.clone();
+                // keep answering tool calls until the assistant replies without any, every reply to a
+                // tool result may ask for more
+                let mut steps = 0;
+                let mut pending = VecDeque::new();
+                loop {
+                    let message = match response {
+                        Ok(message) => message,
+                        Err(e) => {
+                            log!("Couldn't send tool result {:?}", e);
+                            set_command_error.set(Some((e, None)));
+                            break;
+                        }
+                    };
 
-                        set_local_messages.update(|messages| {
-                            for tool_call in tool_calls_data {
+                    if let Some(tool_calls) = message.tool_calls.filter(|calls| !calls.is_empty()) {
+                        if steps >= max_steps {
+                            set_local_messages.update(|messages| {
                                 messages.push(ChatMessage {
                                     id: Uuid::new_v4().to_string(),
                                     role: "system".to_string(),
-                                    content: Some("Implementing changes... ".to_string() + &tool_call.function.name + " " + &tool_call.function.arguments),
+                                    content: Some(format!("Stopped after {} tool steps", max_steps)),
                                     tool_call_id: None,
                                     tool_calls: None,
                                 });
-                            }
-                        });
-
-                        for tool_call in tool_calls {
-                            let result = execute_tool_call(&tool_call, pipeline_store).await;
+                            });
+                            break;
                         }
+                        steps += 1;
+
+                        log!("Tool calls, step {}", steps);
+                        pending.extend(tool_calls);
                     }
+
+                    let Some(tool_call) = pending.pop_front() else {
+                        break;
+                    };
+                    let result = run_tool_call(tool_call.clone()).await;
+
+                    if history.with_untracked(|h| h.is_dirty()) {
+                        save_project().await;
+                    }
+
+                    response = send_chat_message(&session.id, "tool", result, Some(tool_call.id), &project.id).await;
+                    set_streaming_content.set(None);
+                    set_refetch_messages.update(|val| *val = true);
                 }
                 
                 set_refetch_messages.update(|val| *val = true);
@@ -655,17 +1114,82 @@ pub fn App() -> impl IntoView {
         }
     };
 
+    let send_message = move |pipeline_store: LocalResource<Option<Rc<RefCell<ExportPipeline>>>>| {
+        send_content(message_content.get(), pipeline_store);
+    };
+
+    let retry_command = move |_| {
+        if let Some((_, Some(action))) = command_error.get_untracked() {
+            set_command_error.set(None);
+            match action {
+                RetryAction::OpenProject(project) => open_project_chat(project),
+                RetryAction::SendMessage(content) => send_content(content, pipeline_store),
+            }
+        }
+    };
+
+    let error_banner = move || {
+        command_error.get().map(|(error, action)| {
+            let can_retry = action.is_some() && error.is_retryable();
+            view! {
+                <div class="error-banner">
+                    <span>{error.user_message()}</span>
+                    <Show when=move || can_retry>
+                        <button on:click=retry_command>{"Retry"}</button>
+                    </Show>
+                    <button on:click=move |_| set_command_error.set(None)>{"Dismiss"}</button>
+                </div>
+            }
+        })
+    };
+
     view! {
         <main class="container">
+            {error_banner}
+
+            <Show
+                when=move || { !show_chat.get() && show_settings.get() }
+                fallback=|| view! { <span>{""}</span> }
+            >
+                <SettingsPanel
+                    projects_resource={projects_resource}
+                    on_close={Callback::new(move |_| set_show_settings.set(false))}
+                />
+            </Show>
+
             <Show
-                when=move || { !show_chat.get() }
+                when=move || { !show_chat.get() && !show_settings.get() }
                 fallback=|| view! { <span>{""}</span> }
             >
             <section class="inbox">
                 <h2>{"Welcome, Alex"}</h2>
                 <h1>{"Projects"}</h1>
 
-                <button class="primary-btn">{"Start New Project"}</button>
+                <Show
+                    when=move || show_new_project.get()
+                    fallback=move || view! {
+                        <button class="primary-btn" on:click=move |_| set_show_new_project.set(true)>{"Start New Project"}</button>
+                    }
+                >
+                    <div class="new-project">
+                        <input
+                            type="text"
+                            placeholder="Project name"
+                            prop:value=move || new_project_name.get()
+                            on:input=move |ev| set_new_project_name.set(event_target_value(&ev))
+                        />
+                        <button
+                            class="primary-btn"
+                            disabled=move || new_project_name.get().trim().is_empty()
+                            on:click=create_project
+                        >
+                            {"Create Project"}
+                        </button>
+                        <button on:click=move |_| set_show_new_project.set(false)>{"Cancel"}</button>
+                    </div>
+                </Show>
+                <button on:click=import_project>{"Import Project"}</button>
+                <button on:click=move |_| set_show_settings.set(true)>{"Settings"}</button>
 
                 <span class="instructions">{"Chat with apps / projects or other content and add people or bots to the conversation. Optionally mark as public."}</span>
 
@@ -691,10 +1215,18 @@ pub fn App() -> impl IntoView {
                                                     .into_iter()
                                                     .map(|project| {
                                                         let p = project.clone();
+                                                        let menu_project = project.clone();
                                                         view! {
-                                                            <div class="inbox-item" on:click=move |_| {
-                                                                open_project_chat(p.clone());
-                                                            }>
+                                                            <div
+                                                                class="inbox-item"
+                                                                on:click=move |_| {
+                                                                    open_project_chat(p.clone());
+                                                                }
+                                                                on:contextmenu=move |ev: web_sys::MouseEvent| {
+                                                                    ev.prevent_default();
+                                                                    set_context_menu.set(Some((menu_project.clone(), ev.client_x(), ev.client_y())));
+                                                                }
+                                                            >
                                                                 <div class="item-icon">
                                                                     <Icon icon=GAME_CONTROLLER color="#AE2983" weight=IconWeight::Fill size="32px" />
                                                                 </div>
@@ -705,17 +1237,22 @@ pub fn App() -> impl IntoView {
                                                                     </div>
 
                                                                     <div class="item-type">
-                                                                        {"Project"} // Hardcoded for now
+                                                                        {match project.component_count {
+                                                                            Some(count) => format!("Project, {} components", count),
+                                                                            None => "Project".to_string(),
+                                                                        }}
                                                                     </div>
 
                                                                     <div class="item-date">
-                                                                        {"N/A"} // No date in ProjectInfo yet
+                                                                        {project.modified_label()}
                                                                     </div>
                                                                 </div>
                                                             </div>
                                                         }
                                                     })
                                                     .collect_view().into_any()
+                                            } else if let Err(e) = project_items {
+                                                view! { <p>{e.user_message()}</p> }.into_view().into_any()
                                             } else {
                                                 view! { <p>{"Error."}</p> }.into_view().into_any()
                                             }
@@ -724,40 +1261,140 @@ pub fn App() -> impl IntoView {
                             </div>
                         </Suspense>
                     </div>
+                    <div class="">
+                        <h3>{"Trash"}</h3>
+                        <Suspense fallback=move || {
+                            view! { <div>"Loading trash..."</div> }
+                        }>
+                            <div class="files-inner">
+                                {move || {
+                                    trash_resource.get().map(|result| {
+                                        match result.as_ref() {
+                                            Ok(items) if items.is_empty() => view! { <p>{"Trash is empty."}</p> }.into_any(),
+                                            Ok(items) => view! {
+                                                {items
+                                                    .iter()
+                                                    .map(|trashed| {
+                                                        let project_id = trashed.id.clone();
+                                                        view! {
+                                                            <div class="inbox-item">
+                                                                <div class="item-title">{trashed.name.clone()}</div>
+                                                                <button on:click=move |_| restore_project(project_id.clone())>{"Restore"}</button>
+                                                            </div>
+                                                        }
+                                                    })
+                                                    .collect_view()}
+                                                <button on:click=empty_trash>{"Empty Trash"}</button>
+                                            }.into_any(),
+                                            Err(e) => view! { <p>{e.user_message()}</p> }.into_any(),
+                                        }
+                                    })
+                                }}
+                            </div>
+                        </Suspense>
+                    </div>
                 </section>
             </section>
             </Show>
 
+            {move || context_menu.get().map(|(project, x, y)| {
+                let rename_project = project.clone();
+                let duplicate_project = project.clone();
+                let export_project = project.clone();
+                let delete_project = project;
+                view! {
+                    <div class="context-menu-backdrop" on:click=move |_| set_context_menu.set(None)>
+                        <div
+                            class="context-menu"
+                            style=format!("left: {}px; top: {}px;", x, y)
+                            on:click=move |ev| ev.stop_propagation()
+                        >
+                            <button on:click=move |_| run_project_action(ProjectAction::Rename, rename_project.clone())>{"Rename"}</button>
+                            <button on:click=move |_| run_project_action(ProjectAction::Duplicate, duplicate_project.clone())>{"Duplicate"}</button>
+                            <button on:click=move |_| run_project_action(ProjectAction::Export, export_project.clone())>{"Export Archive"}</button>
+                            <button on:click=move |_| run_project_action(ProjectAction::Delete, delete_project.clone())>{"Move to Trash"}</button>
+                        </div>
+                    </div>
+                }
+            })}
+
             <Show
                 when=move || { show_chat.get() }
                 fallback=|| view! { <span>{""}</span> }
             >
             <section class="chat-view">
                 <div class="chat-pane">
-                    <h3>{"Chat with "} {move || selected_project.get().map(|p| p.name).unwrap_or_default()}</h3>
-                    <button on:click=move |_| set_show_chat.set(false)>{"Close Chat"}</button>
+                    <div class="chat-header">
+                        <h3>{"Chat with "} {move || selected_project.get().map(|p| p.name).unwrap_or_default()}</h3>
+                        <span class="save-status" class:dirty=move || history.with(|h| h.is_dirty())>
+                            {move || {
+                                if is_saving.get() {
+                                    "Saving..."
+                                } else if history.with(|h| h.is_dirty()) {
+                                    "Unsaved changes"
+                                } else {
+                                    "Saved"
+                                }
+                            }}
+                        </span>
+                        <button
+                            disabled=move || is_saving.get() || !history.with(|h| h.is_dirty())
+                            on:click=move |_| spawn_local(async move {
+                                save_project().await;
+                            })
+                        >
+                            {"Save"}
+                        </button>
+                    </div>
+                    <button on:click=move |_| close_chat()>{"Close Chat"}</button>
+                    <Show when=move || close_prompt.get()>
+                        <div class="close-prompt">
+                            <span>{"This project has unsaved changes."}</span>
+                            <button disabled=move || is_saving.get() on:click=move |_| save_and_close()>{"Save"}</button>
+                            <button disabled=move || is_saving.get() on:click=move |_| discard_and_close()>{"Discard"}</button>
+                            <button on:click=move |_| set_close_prompt.set(false)>{"Cancel"}</button>
+                        </div>
+                    </Show>
                     <div class="chat-messages">
                         <Suspense fallback=move || {
                             view! { <div>"Loading messages..."</div> }
                         }>
                             {move || {
-                                messages_resource.get().and_then(|result| {
-                                    result.as_ref().ok().map(|messages| {
-                                        messages
+                                messages_resource.get().map(|result| {
+                                    match result.as_ref() {
+                                        Ok(messages) => messages
                                             .into_iter()
                                             .map(|message| {
-                                                view! {
-                                                    <div class="chat-message">
-                                                        <strong>{message.role.clone()}":"</strong>
-                                                        <span>{message.content.clone().unwrap_or_default()}</span>
-                                                    </div>
+                                                // tool results are JSON meant for the model, only failures are worth showing as a card
+                                                let failure = (message.role == "tool")
+                                                    .then(|| message.content.as_deref().and_then(|c| serde_json::from_str::<ToolResult>(c).ok()))
+                                                    .flatten()
+                                                    .filter(|result| !result.success);
+                                                match failure {
+                                                    Some(result) => view! { <ToolFailureCard result=result /> }.into_any(),
+                                                    None => view! {
+                                                        <div class="chat-message">
+                                                            <strong>{message.role.clone()}":"</strong>
+                                                            <span>{message.content.clone().unwrap_or_default()}</span>
+                                                        </div>
+                                                    }.into_any(),
                                                 }
                                             })
                                             .collect_view()
-                                    })
+                                            .into_any(),
+                                        Err(e) => view! {
+                                            <div class="chat-message chat-error">{e.user_message()}</div>
+                                        }.into_any(),
+                                    }
                                 })
                             }}
                         </Suspense>
+                        <Show when=move || streaming_content.get().is_some()>
+                            <div class="chat-message streaming">
+                                <strong>{"assistant:"}</strong>
+                                <span>{move || streaming_content.get().unwrap_or_default()}</span>
+                            </div>
+                        </Show>
                     </div>
                     <div class="chat-input">
                         <input
@@ -778,11 +1415,33 @@ pub fn App() -> impl IntoView {
                         pipeline_store={pipeline_store}
                         is_initialized={is_initialized}
                         set_is_initialized={set_is_initialized} 
+                        extras={extras}
                     />
+                    <div class="history-toolbar">
+                        <button
+                            disabled=move || history.with(|h| h.undo_label().is_none())
+                            title=move || history.with(|h| h.undo_label().map(|l| format!("Undo {}", l)).unwrap_or_default())
+                            on:click=move |_| undo()
+                        >
+                            {"Undo"}
+                        </button>
+                        <button
+                            disabled=move || history.with(|h| h.redo_label().is_none())
+                            title=move || history.with(|h| h.redo_label().map(|l| format!("Redo {}", l)).unwrap_or_default())
+                            on:click=move |_| redo()
+                        >
+                            {"Redo"}
+                        </button>
+                        <button on:click=move |_| import_model()>{"Import Model"}</button>
+                    </div>
                     <ComponentPropertiesEditor
                         pipeline_store={pipeline_store}
                         is_initialized={is_initialized}
+                        state_revision={state_revision}
+                        edit_scope={edit_scope}
                     />
+                    <ControlsPanel edit_scope={edit_scope} />
+                    <CameraPanel edit_scope={edit_scope} />
                 </div>
             </section>
             </Show>
@@ -0,0 +1,131 @@
+use leptos::task::spawn_local;
+use leptos::{prelude::*};
+
+use crate::extras::{CameraMode, CameraSettings};
+use crate::history::{edit_extras, EditScope};
+
+fn finite_value(ev: &leptos::web_sys::Event) -> Option<f32> {
+    event_target_value(ev).trim().parse::<f32>().ok().filter(|v| v.is_finite())
+}
+
+// "x, y, z" as typed into a point input
+fn parse_point(text: &str) -> Option<[f32; 3]> {
+    let values: Vec<f32> = text
+        .split(',')
+        .map(|v| v.trim().parse::<f32>().ok().filter(|v| v.is_finite()))
+        .collect::<Option<_>>()?;
+    values.try_into().ok()
+}
+
+fn format_point(point: Option<[f32; 3]>) -> String {
+    point.map(|p| format!("{}, {}, {}", p[0], p[1], p[2])).unwrap_or_default()
+}
+
+#[component]
+pub fn CameraPanel(
+    edit_scope: EditScope,
+) -> impl IntoView {
+    let (is_open, set_is_open) = signal(false);
+    let (status, set_status) = signal::<Option<String>>(None);
+
+    let settings = move || edit_scope.extras.with(|e| e.camera.clone()).unwrap_or_default();
+
+    // applies an edit to the game camera when the result is valid, keeping the message otherwise
+    let update = move |label: &'static str, edit: Box<dyn FnOnce(&mut CameraSettings)>| {
+        let mut camera = edit_scope.extras.with_untracked(|e| e.camera.clone()).unwrap_or_default();
+        edit(&mut camera);
+        if let Some((field, message)) = camera.issues().into_iter().next() {
+            set_status.set(Some(format!("{}: {}", field, message)));
+            return;
+        }
+        set_status.set(None);
+
+        let Some(ctx) = edit_scope.context() else {
+            return;
+        };
+        spawn_local(async move {
+            edit_extras(&ctx, label, |extras| extras.camera = Some(camera)).await;
+        });
+    };
+
+    let number = move |label: &'static str, value: fn(&CameraSettings) -> f32, set: fn(&mut CameraSettings, f32)| {
+        view! {
+            <input
+                type="number"
+                step="0.1"
+                prop:value=move || value(&settings())
+                on:change=move |ev| {
+                    if let Some(v) = finite_value(&ev) {
+                        update(label, Box::new(move |c| set(c, v)));
+                    }
+                }
+            />
+        }
+    };
+
+    let point = move |label: &'static str, value: fn(&CameraSettings) -> Option<[f32; 3]>, set: fn(&mut CameraSettings, [f32; 3])| {
+        view! {
+            <input
+                type="text"
+                placeholder="x, y, z"
+                prop:value=move || format_point(value(&settings()))
+                on:change=move |ev| match parse_point(&event_target_value(&ev)) {
+                    Some(p) => update(label, Box::new(move |c| set(c, p))),
+                    None => set_status.set(Some("Points are written as three numbers, x, y, z".to_string())),
+                }
+            />
+        }
+    };
+
+    let mode = move || settings().mode;
+
+    view! {
+        <details class="camera-panel" open=is_open.get() on:toggle=move |_| set_is_open.update(|v| *v = !*v)>
+            <summary>{"Game Camera"}</summary>
+            <div class="property-group">
+                <label>
+                    {"Mode: "}
+                    <select on:change=move |ev| {
+                        let mode = match event_target_value(&ev).as_str() {
+                            "firstPerson" => CameraMode::FirstPerson,
+                            "topDown" => CameraMode::TopDown,
+                            "fixed" => CameraMode::Fixed,
+                            _ => CameraMode::ThirdPerson,
+                        };
+                        update("Change camera mode", Box::new(move |c| {
+                            c.mode = mode;
+                            // a fixed camera needs a spot to start from before it can be moved
+                            if mode == CameraMode::Fixed {
+                                c.position.get_or_insert([0.0, 10.0, -10.0]);
+                                c.target.get_or_insert([0.0, 0.0, 0.0]);
+                            }
+                        }));
+                    }>
+                        <option value="firstPerson" selected=move || mode() == CameraMode::FirstPerson>{"First Person"}</option>
+                        <option value="thirdPerson" selected=move || mode() == CameraMode::ThirdPerson>{"Third Person"}</option>
+                        <option value="topDown" selected=move || mode() == CameraMode::TopDown>{"Top-Down"}</option>
+                        <option value="fixed" selected=move || mode() == CameraMode::Fixed>{"Fixed"}</option>
+                    </select>
+                </label>
+
+                <Show when=move || mode() == CameraMode::ThirdPerson>
+                    <label>{"Distance: "}{number("Change camera distance", |c| c.distance, |c, v| c.distance = v)}</label>
+                    <label>{"Lag: "}{number("Change camera lag", |c| c.lag, |c, v| c.lag = v)}</label>
+                </Show>
+                <Show when=move || matches!(mode(), CameraMode::ThirdPerson | CameraMode::TopDown)>
+                    <label>{"Height: "}{number("Change camera height", |c| c.height, |c, v| c.height = v)}</label>
+                </Show>
+                <Show when=move || mode() == CameraMode::Fixed>
+                    <label>{"Position: "}{point("Move camera", |c| c.position, |c, p| c.position = Some(p))}</label>
+                    <label>{"Look At: "}{point("Aim camera", |c| c.target, |c, p| c.target = Some(p))}</label>
+                </Show>
+
+                <label>{"Field of View: "}{number("Change field of view", |c| c.fov_degrees, |c, v| c.fov_degrees = v)}</label>
+                <label>{"Near Plane: "}{number("Change near plane", |c| c.near, |c, v| c.near = v)}</label>
+                <label>{"Far Plane: "}{number("Change far plane", |c| c.far, |c, v| c.far = v)}</label>
+
+                <span class="instructions">{move || status.get().unwrap_or_default()}</span>
+            </div>
+        </details>
+    }
+}
@@ -34,16 +34,48 @@ use entropy_engine::helpers::saved_data::{
 };
 use std::time::{Duration, SystemTime};
 
+use crate::extras::{NpcMovement, NpcReaction};
+use crate::history::{edit_component, EditScope};
+
+/// An `on:change` handler which applies a numeric input to the component as an undoable edit
+fn edit_number(
+    edit_scope: EditScope,
+    component_id: String,
+    label: &'static str,
+    edit: impl Fn(&mut ComponentData, f32) + Copy + 'static,
+) -> impl Fn(web_sys::Event) + 'static {
+    move |ev| {
+        let Ok(value) = event_target_value(&ev).trim().parse::<f32>() else {
+            return;
+        };
+        if !value.is_finite() {
+            return;
+        }
+        let Some(ctx) = edit_scope.context() else {
+            return;
+        };
+        let component_id = component_id.clone();
+        spawn_local(async move {
+            edit_component(&ctx, label, &component_id, |component| edit(component, value)).await;
+        });
+    }
+}
+
 #[component]
 pub fn ComponentPropertiesEditor(
     pipeline_store: LocalResource<Option<Rc<RefCell<ExportPipeline>>>>,
     is_initialized: ReadSignal<bool>,
+    state_revision: ReadSignal<u32>,
+    edit_scope: EditScope,
 ) -> impl IntoView {
     let (selected_component_id, set_selected_component_id) = signal::<Option<String>>(None);
     let (components_list, set_components_list) = signal::<Vec<ComponentData>>(Vec::new());
     
     // Extract components in an effect to avoid borrow issues
     create_effect(move |_| {
+        // re-read whenever a command changes the saved state
+        state_revision.track();
+
         if is_initialized.get() {
             if let Some(pipeline) = pipeline_store.get() {
                 if let Some(pipeline_arc) = pipeline.as_ref() {
@@ -97,6 +129,7 @@ pub fn ComponentPropertiesEditor(
                                     <Show when=is_selected>
                                         <ComponentPropertyPanel 
                                             component=component.clone()
+                                            edit_scope=edit_scope
                                         />
                                     </Show>
                                 </div>
@@ -112,6 +145,7 @@ pub fn ComponentPropertiesEditor(
 #[component]
 fn ComponentPropertyPanel(
     component: ComponentData,
+    edit_scope: EditScope,
 ) -> impl IntoView {
     view! {
         <div class="property-panel">
@@ -119,6 +153,7 @@ fn ComponentPropertyPanel(
             <GenericPropertiesPanel 
                 generic=component.generic_properties.clone() 
                 component_id=component.id.clone()
+                edit_scope=edit_scope
             />
             
             // Component-specific properties
@@ -134,6 +169,7 @@ fn ComponentPropertyPanel(
                     <NPCPropertiesPanel 
                         properties=component.npc_properties.clone().unwrap_or_default()
                         component_id=component.id.clone()
+                        edit_scope=edit_scope
                     />
                 }.into_view().into_any(),
                 
@@ -155,6 +191,7 @@ fn ComponentPropertyPanel(
                     <WaterPropertiesPanel 
                         properties=component.water_properties.clone()
                         component_id=component.id.clone()
+                        edit_scope=edit_scope
                     />
                 }.into_view().into_any(),
                 
@@ -190,7 +227,9 @@ fn ComponentPropertyPanel(
 fn GenericPropertiesPanel(
     generic: GenericProperties,
     component_id: String,
+    edit_scope: EditScope,
 ) -> impl IntoView {
+
     let (is_open, set_is_open) = signal(true);
     
     view! {
@@ -204,41 +243,50 @@ fn GenericPropertiesPanel(
                 
                 <label>
                     {"Position X: "}
-                    <input type="number" step="0.1" value=generic.position[0] />
+                    <input type="number" step="0.1" value=generic.position[0]
+                        on:change=edit_number(edit_scope, component_id.clone(), "Move X", |c, v| c.generic_properties.position[0] = v) />
                 </label>
                 <label>
                     {"Position Y: "}
-                    <input type="number" step="0.1" value=generic.position[1] />
+                    <input type="number" step="0.1" value=generic.position[1]
+                        on:change=edit_number(edit_scope, component_id.clone(), "Move Y", |c, v| c.generic_properties.position[1] = v) />
                 </label>
                 <label>
                     {"Position Z: "}
-                    <input type="number" step="0.1" value=generic.position[2] />
+                    <input type="number" step="0.1" value=generic.position[2]
+                        on:change=edit_number(edit_scope, component_id.clone(), "Move Z", |c, v| c.generic_properties.position[2] = v) />
                 </label>
                 
                 <label>
                     {"Rotation X: "}
-                    <input type="number" step="1" value=generic.rotation[0] />
+                    <input type="number" step="1" value=generic.rotation[0]
+                        on:change=edit_number(edit_scope, component_id.clone(), "Rotate X", |c, v| c.generic_properties.rotation[0] = v) />
                 </label>
                 <label>
                     {"Rotation Y: "}
-                    <input type="number" step="1" value=generic.rotation[1] />
+                    <input type="number" step="1" value=generic.rotation[1]
+                        on:change=edit_number(edit_scope, component_id.clone(), "Rotate Y", |c, v| c.generic_properties.rotation[1] = v) />
                 </label>
                 <label>
                     {"Rotation Z: "}
-                    <input type="number" step="1" value=generic.rotation[2] />
+                    <input type="number" step="1" value=generic.rotation[2]
+                        on:change=edit_number(edit_scope, component_id.clone(), "Rotate Z", |c, v| c.generic_properties.rotation[2] = v) />
                 </label>
                 
                 <label>
                     {"Scale X: "}
-                    <input type="number" step="0.1" value=generic.scale[0] />
+                    <input type="number" step="0.1" value=generic.scale[0]
+                        on:change=edit_number(edit_scope, component_id.clone(), "Scale X", |c, v| c.generic_properties.scale[0] = v) />
                 </label>
                 <label>
                     {"Scale Y: "}
-                    <input type="number" step="0.1" value=generic.scale[1] />
+                    <input type="number" step="0.1" value=generic.scale[1]
+                        on:change=edit_number(edit_scope, component_id.clone(), "Scale Y", |c, v| c.generic_properties.scale[1] = v) />
                 </label>
                 <label>
                     {"Scale Z: "}
-                    <input type="number" step="0.1" value=generic.scale[2] />
+                    <input type="number" step="0.1" value=generic.scale[2]
+                        on:change=edit_number(edit_scope, component_id.clone(), "Scale Z", |c, v| c.generic_properties.scale[2] = v) />
                 </label>
             </div>
         </details>
@@ -266,8 +314,13 @@ fn ModelPropertiesPanel(
 fn NPCPropertiesPanel(
     properties: NPCProperties,
     component_id: String,
+    edit_scope: EditScope,
 ) -> impl IntoView {
     let (is_open, set_is_open) = signal(false);
+    let behavior = edit_scope
+        .extras
+        .with_untracked(|extras| extras.npcs.get(&component_id).cloned())
+        .unwrap_or_default();
     
     view! {
         <details open=is_open.get() on:toggle=move |_| set_is_open.update(|v| *v = !*v)>
@@ -277,6 +330,23 @@ fn NPCPropertiesPanel(
                     {"Model ID: "}
                     <input type="text" value=properties.model_id />
                 </label>
+
+                <label>
+                    {"Movement: "}
+                    <span>{match behavior.movement {
+                        NpcMovement::Idle => "Idle".to_string(),
+                        NpcMovement::Wander => format!("Wander within {}", behavior.wander_radius),
+                        NpcMovement::Patrol => format!("Patrol {} waypoints", behavior.patrol_waypoints.len()),
+                    }}</span>
+                </label>
+                <label>
+                    {"When the player is near: "}
+                    <span>{match behavior.reaction {
+                        NpcReaction::Ignore => "Ignore".to_string(),
+                        NpcReaction::Chase => format!("Chase within {}", behavior.aggro_range),
+                        NpcReaction::Flee => format!("Flee within {}", behavior.aggro_range),
+                    }}</span>
+                </label>
             </div>
         </details>
     }
@@ -374,6 +444,7 @@ fn LightPropertiesPanel(
 fn WaterPropertiesPanel(
     properties: Option<WaterConfig>,
     component_id: String,
+    edit_scope: EditScope,
 ) -> impl IntoView {
     let (is_open, set_is_open) = signal(false);
     
@@ -381,11 +452,97 @@ fn WaterPropertiesPanel(
         <details open=is_open.get() on:toggle=move |_| set_is_open.update(|v| *v = !*v)>
             <summary>{"Water Properties"}</summary>
             <div class="property-group">
-                {properties.map(|_config| view! {
-                    <p class="info-text">{"Water configuration available (structure depends on WaterConfig)"}</p>
-                }).unwrap_or_else(|| view! {
+                {properties.map(|config| view! {
+                    <label>
+                        {"Shallow Color: "}
+                        <input type="text" value=format!("{:.2}, {:.2}, {:.2}", config.shallow_color[0], config.shallow_color[1], config.shallow_color[2]) />
+                    </label>
+                    <label>
+                        {"Medium Color: "}
+                        <input type="text" value=format!("{:.2}, {:.2}, {:.2}", config.medium_color[0], config.medium_color[1], config.medium_color[2]) />
+                    </label>
+                    <label>
+                        {"Deep Color: "}
+                        <input type="text" value=format!("{:.2}, {:.2}, {:.2}", config.deep_color[0], config.deep_color[1], config.deep_color[2]) />
+                    </label>
+
+                    <label>
+                        {"Ripple Amplitude: "}
+                        <input type="number" step="0.1" min="0" value=config.ripple_amplitude_multiplier
+                            on:change=edit_number(edit_scope, component_id.clone(), "Ripple amplitude", |c, v| {
+                                if let Some(water) = c.water_properties.as_mut() {
+                                    water.ripple_amplitude_multiplier = v;
+                                }
+                            }) />
+                    </label>
+                    <label>
+                        {"Ripple Frequency: "}
+                        <input type="number" step="0.1" min="0" value=config.ripple_freq
+                            on:change=edit_number(edit_scope, component_id.clone(), "Ripple frequency", |c, v| {
+                                if let Some(water) = c.water_properties.as_mut() {
+                                    water.ripple_freq = v;
+                                }
+                            }) />
+                    </label>
+                    <label>
+                        {"Ripple Speed: "}
+                        <input type="number" step="0.1" value=config.ripple_speed
+                            on:change=edit_number(edit_scope, component_id.clone(), "Ripple speed", |c, v| {
+                                if let Some(water) = c.water_properties.as_mut() {
+                                    water.ripple_speed = v;
+                                }
+                            }) />
+                    </label>
+
+                    <label>
+                        {"Wave 1 Amplitude: "}
+                        <input type="number" step="0.1" min="0" value=config.wave1_amplitude
+                            on:change=edit_number(edit_scope, component_id.clone(), "Wave 1 amplitude", |c, v| {
+                                if let Some(water) = c.water_properties.as_mut() {
+                                    water.wave1_amplitude = v;
+                                }
+                            }) />
+                    </label>
+                    <label>
+                        {"Wave 2 Amplitude: "}
+                        <input type="number" step="0.1" min="0" value=config.wave2_amplitude
+                            on:change=edit_number(edit_scope, component_id.clone(), "Wave 2 amplitude", |c, v| {
+                                if let Some(water) = c.water_properties.as_mut() {
+                                    water.wave2_amplitude = v;
+                                }
+                            }) />
+                    </label>
+                    <label>
+                        {"Wave 3 Amplitude: "}
+                        <input type="number" step="0.1" min="0" value=config.wave3_amplitude
+                            on:change=edit_number(edit_scope, component_id.clone(), "Wave 3 amplitude", |c, v| {
+                                if let Some(water) = c.water_properties.as_mut() {
+                                    water.wave3_amplitude = v;
+                                }
+                            }) />
+                    </label>
+
+                    <label>
+                        {"Fresnel Power: "}
+                        <input type="number" step="0.1" min="0" value=config.fresnel_power
+                            on:change=edit_number(edit_scope, component_id.clone(), "Fresnel power", |c, v| {
+                                if let Some(water) = c.water_properties.as_mut() {
+                                    water.fresnel_power = v;
+                                }
+                            }) />
+                    </label>
+                    <label>
+                        {"Sparkle Intensity: "}
+                        <input type="number" step="0.1" min="0" value=config.sparkle_intensity
+                            on:change=edit_number(edit_scope, component_id.clone(), "Sparkle intensity", |c, v| {
+                                if let Some(water) = c.water_properties.as_mut() {
+                                    water.sparkle_intensity = v;
+                                }
+                            }) />
+                    </label>
+                }.into_any()).unwrap_or_else(|| view! {
                     <p class="info-text">{"No water configuration"}</p>
-                })}
+                }.into_any())}
             </div>
         </details>
     }
@@ -0,0 +1,69 @@
+use leptos::task::spawn_local;
+use leptos::{prelude::*};
+
+use crate::extras::{normalize_input, GameAction, InputMap};
+use crate::history::{edit_extras, EditScope};
+
+#[component]
+pub fn ControlsPanel(
+    edit_scope: EditScope,
+) -> impl IntoView {
+    let (is_open, set_is_open) = signal(false);
+    let (status, set_status) = signal::<Option<String>>(None);
+
+    // replaces the bindings when they're valid, keeping the last message otherwise
+    let set_controls = move |label: &'static str, controls: InputMap| {
+        if let Some((field, message)) = controls.issues().into_iter().next() {
+            set_status.set(Some(format!("{}: {}", field, message)));
+            return;
+        }
+        set_status.set(None);
+
+        let Some(ctx) = edit_scope.context() else {
+            return;
+        };
+        spawn_local(async move {
+            edit_extras(&ctx, label, |extras| extras.controls = controls).await;
+        });
+    };
+
+    view! {
+        <details class="controls-panel" open=is_open.get() on:toggle=move |_| set_is_open.update(|v| *v = !*v)>
+            <summary>{"Controls"}</summary>
+            <div class="property-group">
+                <p class="info-text">
+                    {"Separate inputs with commas. Use single characters, named keys like Space or Shift, or Mouse0, Mouse1 and Mouse2."}
+                </p>
+                {GameAction::ALL.into_iter().map(|action| view! {
+                    <label>
+                        {format!("{}: ", action.label())}
+                        <input
+                            type="text"
+                            prop:value=move || edit_scope.extras.with(|e| e.controls.inputs(action).join(", "))
+                            on:change=move |ev| {
+                                let inputs: Vec<String> = event_target_value(&ev)
+                                    .split(',')
+                                    .map(str::trim)
+                                    .filter(|i| !i.is_empty())
+                                    .map(normalize_input)
+                                    .collect();
+                                let mut controls = edit_scope.extras.with_untracked(|e| e.controls.clone());
+                                controls.bindings.insert(action, inputs);
+                                set_controls("Change controls", controls);
+                            }
+                        />
+                    </label>
+                }).collect_view()}
+
+                <span class="instructions">{move || status.get().unwrap_or_default()}</span>
+
+                <button
+                    disabled=move || edit_scope.extras.with(|e| e.controls.is_empty())
+                    on:click=move |_| set_controls("Reset controls", InputMap::default())
+                >
+                    {"Reset to Defaults"}
+                </button>
+            </div>
+        </details>
+    }
+}
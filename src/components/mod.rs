@@ -1 +1,5 @@
-pub mod component_browser;
\ No newline at end of file
+pub mod camera_panel;
+pub mod component_browser;
+pub mod controls_panel;
+pub mod settings_panel;
+pub mod tool_result_card;
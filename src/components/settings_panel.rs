@@ -0,0 +1,189 @@
+use leptos::task::spawn_local;
+use leptos::{prelude::*};
+use leptos::logging::log;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::app::{invoke_command, ProjectInfo};
+use crate::errors::CommandError;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AppSettings {
+    pub api_url: String,
+    pub request_timeout_secs: u64,
+    pub max_agent_steps: u32,
+    pub project_overrides: HashMap<String, ProjectSettings>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SettingsView {
+    pub settings: AppSettings,
+    pub env_api_url: Option<String>,
+}
+
+#[component]
+pub fn SettingsPanel(
+    projects_resource: LocalResource<Result<Vec<ProjectInfo>, CommandError>>,
+    on_close: Callback<()>,
+) -> impl IntoView {
+    let (draft, set_draft) = signal::<Option<AppSettings>>(None);
+    let (env_api_url, set_env_api_url) = signal::<Option<String>>(None);
+    let (status, set_status) = signal::<Option<String>>(None);
+
+    spawn_local(async move {
+        match invoke_command::<_, SettingsView>("get_settings", &()).await {
+            Ok(view) => {
+                set_env_api_url.set(view.env_api_url);
+                set_draft.set(Some(view.settings));
+            }
+            Err(e) => {
+                log!("Couldn't load settings {:?}", e);
+                set_status.set(Some(e.user_message()));
+            }
+        }
+    });
+
+    let save_settings = move |_| {
+        let Some(settings) = draft.get_untracked() else {
+            return;
+        };
+
+        spawn_local(async move {
+            #[derive(Serialize)]
+            #[serde(rename_all = "camelCase")]
+            struct UpdateSettingsArgs {
+                new_settings: AppSettings,
+            }
+
+            let result: Result<SettingsView, CommandError> =
+                invoke_command("update_settings", &UpdateSettingsArgs { new_settings: settings }).await;
+            match result {
+                Ok(view) => {
+                    set_env_api_url.set(view.env_api_url);
+                    set_draft.set(Some(view.settings));
+                    set_status.set(Some("Settings saved".to_string()));
+                }
+                Err(e) => {
+                    log!("Couldn't save settings {:?}", e);
+                    set_status.set(Some(e.user_message()));
+                }
+            }
+        });
+    };
+
+    view! {
+        <section class="settings-panel">
+            <h2>{"Settings"}</h2>
+
+            <Show when=move || env_api_url.get().is_some()>
+                <p class="info-text">
+                    {"ENTROPY_API_URL is set, so every request goes to "}
+                    {move || env_api_url.get().unwrap_or_default()}
+                </p>
+            </Show>
+
+            <Show
+                when=move || draft.get().is_some()
+                fallback=|| view! { <div>{"Loading settings..."}</div> }
+            >
+                <div class="settings-group">
+                    <label>
+                        {"Entropy API URL: "}
+                        <input
+                            type="text"
+                            prop:value=move || draft.get().map(|s| s.api_url).unwrap_or_default()
+                            on:input=move |ev| {
+                                let value = event_target_value(&ev);
+                                set_draft.update(|d| if let Some(d) = d.as_mut() { d.api_url = value; });
+                            }
+                        />
+                    </label>
+
+                    <label>
+                        {"Request Timeout (seconds): "}
+                        <input
+                            type="number"
+                            min="1"
+                            step="1"
+                            prop:value=move || draft.get().map(|s| s.request_timeout_secs).unwrap_or_default()
+                            on:input=move |ev| {
+                                if let Ok(value) = event_target_value(&ev).parse::<u64>() {
+                                    set_draft.update(|d| if let Some(d) = d.as_mut() { d.request_timeout_secs = value; });
+                                }
+                            }
+                        />
+                    </label>
+
+                    <label>
+                        {"Max Tool Steps per Message: "}
+                        <input
+                            type="number"
+                            min="1"
+                            step="1"
+                            prop:value=move || draft.get().map(|s| s.max_agent_steps).unwrap_or_default()
+                            on:input=move |ev| {
+                                if let Ok(value) = event_target_value(&ev).parse::<u32>() {
+                                    set_draft.update(|d| if let Some(d) = d.as_mut() { d.max_agent_steps = value; });
+                                }
+                            }
+                        />
+                    </label>
+                </div>
+
+                <h3>{"Project Overrides"}</h3>
+                <div class="settings-group">
+                    {move || {
+                        projects_resource.get().and_then(|result| result.as_ref().ok().cloned()).map(|projects| {
+                            projects
+                                .into_iter()
+                                .map(|project| {
+                                    let project_id = project.id.clone();
+                                    let input_project_id = project.id.clone();
+
+                                    view! {
+                                        <label>
+                                            {project.name.clone()}{": "}
+                                            <input
+                                                type="text"
+                                                placeholder="(uses app setting)"
+                                                prop:value=move || {
+                                                    draft
+                                                        .get()
+                                                        .and_then(|s| s.project_overrides.get(&project_id).cloned())
+                                                        .and_then(|o| o.api_url)
+                                                        .unwrap_or_default()
+                                                }
+                                                on:input=move |ev| {
+                                                    let value = event_target_value(&ev);
+                                                    let project_id = input_project_id.clone();
+                                                    set_draft.update(|d| if let Some(d) = d.as_mut() {
+                                                        d.project_overrides.entry(project_id).or_default().api_url = Some(value);
+                                                    });
+                                                }
+                                            />
+                                        </label>
+                                    }
+                                })
+                                .collect_view()
+                        })
+                    }}
+                </div>
+            </Show>
+
+            <Show when=move || status.get().is_some()>
+                <span class="instructions">{move || status.get().unwrap_or_default()}</span>
+            </Show>
+
+            <div class="settings-actions">
+                <button class="primary-btn" on:click=save_settings>{"Save Settings"}</button>
+                <button on:click=move |_| on_close.run(())>{"Back to Projects"}</button>
+            </div>
+        </section>
+    }
+}
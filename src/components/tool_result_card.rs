@@ -0,0 +1,42 @@
+use leptos::prelude::*;
+
+use crate::tools::{ToolError, ToolResult};
+
+/// Shows a failed tool call in the chat, listing each bad field the way the model was told about it
+#[component]
+pub fn ToolFailureCard(result: ToolResult) -> impl IntoView {
+    let error = result.error.unwrap_or_else(|| ToolError::failed("The tool didn't say what went wrong"));
+
+    let details = match error {
+        ToolError::UnknownTool { name, available } => view! {
+            <p>{format!("The assistant asked for a tool called {:?}, which doesn't exist.", name)}</p>
+            <p class="tool-failure-hint">{format!("Available tools: {}", available.join(", "))}</p>
+        }
+        .into_any(),
+        ToolError::InvalidArguments { issues } => view! {
+            <ul class="tool-failure-issues">
+                {issues
+                    .into_iter()
+                    .map(|issue| {
+                        view! {
+                            <li>
+                                <code>{issue.field}</code>
+                                {" "}
+                                {issue.message}
+                            </li>
+                        }
+                    })
+                    .collect_view()}
+            </ul>
+        }
+        .into_any(),
+        ToolError::Failed { message } => view! { <p>{message}</p> }.into_any(),
+    };
+
+    view! {
+        <div class="chat-message tool-failure">
+            <strong>{format!("{} failed", result.tool)}</strong>
+            {details}
+        </div>
+    }
+}
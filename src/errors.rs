@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+
+/// Mirrors `CommandError` in src-tauri, plus the failures which can only happen on this side
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum CommandError {
+    ApiUnreachable { url: String, message: String },
+    ApiStatus { code: u16, body: String },
+    ProjectNotFound { project_id: String },
+    SavedStateCorrupt { project_id: String, message: String },
+    Validation { issues: Vec<ValidationIssue> },
+    Decode { message: String },
+    InvalidSettings { message: String },
+    Io { message: String },
+    // Tauri itself rejected the call, e.g. a missing command or bad arguments
+    Unknown { message: String },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    pub field: String,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        ValidationIssue {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl CommandError {
+    pub fn from_js(value: JsValue) -> Self {
+        if let Some(message) = value.as_string() {
+            return CommandError::Unknown { message };
+        }
+
+        serde_wasm_bindgen::from_value(value.clone()).unwrap_or_else(|_| CommandError::Unknown {
+            message: format!("{:?}", value),
+        })
+    }
+
+    /// Whether trying the same thing again could reasonably succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            CommandError::ApiUnreachable { .. } => true,
+            CommandError::ApiStatus { code, .. } => *code >= 500,
+            _ => false,
+        }
+    }
+
+    pub fn user_message(&self) -> String {
+        match self {
+            CommandError::ApiUnreachable { url, .. } => {
+                format!("Couldn't reach the Entropy API{}. Check that it is running or change the URL in Settings.",
+                    if url.is_empty() { String::new() } else { format!(" at {}", url) })
+            }
+            CommandError::ApiStatus { code, body } if *code >= 500 => {
+                format!("The Entropy API had a problem ({}). {}", code, body)
+            }
+            CommandError::ApiStatus { code, body } => {
+                format!("The Entropy API rejected the request ({}). {}", code, body)
+            }
+            CommandError::ProjectNotFound { project_id } => {
+                format!("Project {} no longer exists on disk.", project_id)
+            }
+            CommandError::SavedStateCorrupt { project_id, message } => {
+                format!("Project {} couldn't be read: {}", project_id, message)
+            }
+            CommandError::Validation { issues } => {
+                let issues = issues
+                    .iter()
+                    .map(|i| format!("{} {}", i.field, i.message))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("Some values are invalid: {}", issues)
+            }
+            CommandError::Decode { message } => {
+                format!("Received a response the app doesn't understand: {}", message)
+            }
+            CommandError::InvalidSettings { message } => message.clone(),
+            CommandError::Io { message } => message.clone(),
+            CommandError::Unknown { message } => message.clone(),
+        }
+    }
+}
@@ -0,0 +1,469 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// What `extras.json` holds, the per-project settings `SavedState` has no fields for, keyed by component id
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectExtras {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub grass: BTreeMap<String, GrassSettings>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub scatter: BTreeMap<String, ScatterRules>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub npcs: BTreeMap<String, NpcBehavior>,
+    #[serde(default, skip_serializing_if = "InputMap::is_empty")]
+    pub controls: InputMap,
+    // the game camera, the viewport takes its lens but stays free to fly around
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub camera: Option<CameraSettings>,
+    // keyed by asset id
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub models: BTreeMap<String, ModelAsset>,
+}
+
+/// How the grass scattered over a landscape looks, its placement lives in the landscape's `ScatterSettings`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GrassSettings {
+    pub blade_height: f32,
+    pub blade_color: [f32; 3],
+    pub wind_strength: f32,
+    pub wind_speed: f32,
+    pub wind_direction: [f32; 2],
+}
+
+impl Default for GrassSettings {
+    fn default() -> Self {
+        GrassSettings {
+            blade_height: 0.5,
+            blade_color: [0.3, 0.6, 0.2],
+            wind_strength: 0.3,
+            wind_speed: 1.0,
+            wind_direction: [1.0, 0.0],
+        }
+    }
+}
+
+/// What the app remembers about a scatter beyond the component's `ScatterSettings`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScatterRules {
+    // the component only exists to hold the scatter, so removing the scatter removes it
+    pub generated: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NpcMovement {
+    #[default]
+    Idle,
+    Wander,
+    Patrol,
+}
+
+/// What an NPC does once the player comes within its aggro range
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NpcReaction {
+    #[default]
+    Ignore,
+    Chase,
+    Flee,
+}
+
+/// The built-in behaviors of an NPC, `NPCProperties` only holds its model
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NpcBehavior {
+    pub movement: NpcMovement,
+    pub wander_radius: f32,
+    pub patrol_waypoints: Vec<[f32; 3]>,
+    pub aggro_range: f32,
+    pub reaction: NpcReaction,
+}
+
+impl Default for NpcBehavior {
+    fn default() -> Self {
+        NpcBehavior {
+            movement: NpcMovement::Idle,
+            wander_radius: 10.0,
+            patrol_waypoints: Vec::new(),
+            aggro_range: 15.0,
+            reaction: NpcReaction::Ignore,
+        }
+    }
+}
+
+/// Something the player can do, bound to keys or mouse buttons through the `InputMap`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GameAction {
+    MoveForward,
+    MoveBackward,
+    MoveLeft,
+    MoveRight,
+    Jump,
+    Attack,
+    Interact,
+    // held while moving the mouse to turn the camera
+    CameraLook,
+}
+
+impl GameAction {
+    pub const ALL: [GameAction; 8] = [
+        GameAction::MoveForward,
+        GameAction::MoveBackward,
+        GameAction::MoveLeft,
+        GameAction::MoveRight,
+        GameAction::Jump,
+        GameAction::Attack,
+        GameAction::Interact,
+        GameAction::CameraLook,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GameAction::MoveForward => "Move Forward",
+            GameAction::MoveBackward => "Move Backward",
+            GameAction::MoveLeft => "Move Left",
+            GameAction::MoveRight => "Move Right",
+            GameAction::Jump => "Jump",
+            GameAction::Attack => "Attack",
+            GameAction::Interact => "Interact",
+            GameAction::CameraLook => "Camera Look",
+        }
+    }
+
+    /// The input the engine itself listens for
+    pub fn default_input(&self) -> &'static str {
+        match self {
+            GameAction::MoveForward => "w",
+            GameAction::MoveBackward => "s",
+            GameAction::MoveLeft => "a",
+            GameAction::MoveRight => "d",
+            GameAction::Jump => "Space",
+            GameAction::Attack => "f",
+            GameAction::Interact => "e",
+            GameAction::CameraLook => "Shift",
+        }
+    }
+
+    /// The key string passed to `handle_key_press` when the action is triggered
+    fn engine_key(&self) -> Option<&'static str> {
+        match self {
+            GameAction::Jump => Some(" "),
+            // read from the modifiers and buttons of mouse moves instead
+            GameAction::CameraLook => None,
+            action => Some(action.default_input()),
+        }
+    }
+}
+
+const NAMED_KEYS: [&str; 12] = [
+    "Space", "Shift", "Control", "Alt", "Enter", "Tab", "Escape", "Backspace", "ArrowUp", "ArrowDown", "ArrowLeft",
+    "ArrowRight",
+];
+const MOUSE_BUTTONS: [&str; 3] = ["Mouse0", "Mouse1", "Mouse2"];
+const MODIFIER_KEYS: [&str; 3] = ["Shift", "Control", "Alt"];
+
+/// Spells a `KeyboardEvent.key` or binding the way the input map stores it
+pub fn normalize_input(input: &str) -> String {
+    let mut chars = input.chars();
+    match (chars.next(), chars.next()) {
+        (Some(' '), None) => "Space".to_string(),
+        (Some(c), None) => c.to_lowercase().collect(),
+        _ => input.trim().to_string(),
+    }
+}
+
+/// The project's bindings of actions to inputs, actions left out keep their default input
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct InputMap {
+    pub bindings: BTreeMap<GameAction, Vec<String>>,
+}
+
+impl InputMap {
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+
+    pub fn inputs(&self, action: GameAction) -> Vec<String> {
+        self.bindings
+            .get(&action)
+            .cloned()
+            .unwrap_or_else(|| vec![action.default_input().to_string()])
+    }
+
+    fn action_for(&self, input: &str) -> Option<GameAction> {
+        GameAction::ALL
+            .into_iter()
+            .find(|action| self.inputs(*action).iter().any(|i| i == input))
+    }
+
+    /// The key to hand the engine for a pressed key or mouse button, `None` when it should be dropped
+    pub fn engine_key(&self, raw: &str) -> Option<String> {
+        let input = normalize_input(raw);
+        match self.action_for(&input) {
+            // whatever is bound reaches the engine as the action's own key, camera look never does
+            Some(action) => action.engine_key().map(str::to_string),
+            // the engine would still react to a default key after its action was moved elsewhere
+            None if GameAction::ALL.iter().any(|a| a.default_input() == input) => None,
+            None if MOUSE_BUTTONS.contains(&input.as_str()) => None,
+            None => Some(raw.to_string()),
+        }
+    }
+
+    /// Whether an action bound to modifiers or mouse buttons is held, given a mouse event's state
+    pub fn is_held(&self, action: GameAction, shift: bool, control: bool, alt: bool, buttons: u16) -> bool {
+        self.inputs(action).iter().any(|input| match input.as_str() {
+            "Shift" => shift,
+            "Control" => control,
+            "Alt" => alt,
+            // `buttons` numbers the middle and right buttons the other way around from `button`
+            "Mouse0" => buttons & 1 != 0,
+            "Mouse1" => buttons & 4 != 0,
+            "Mouse2" => buttons & 2 != 0,
+            _ => false,
+        })
+    }
+
+    /// Everything wrong with the bindings, as the binding and what's wrong with it
+    pub fn issues(&self) -> Vec<(String, String)> {
+        let mut issues = Vec::new();
+
+        for (action, inputs) in &self.bindings {
+            let field = serde_json::to_value(action)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_default();
+
+            if inputs.is_empty() {
+                issues.push((field.clone(), "needs at least one input".to_string()));
+            }
+            for input in inputs {
+                let known = input.chars().count() == 1
+                    || NAMED_KEYS.contains(&input.as_str())
+                    || MOUSE_BUTTONS.contains(&input.as_str());
+                if !known {
+                    issues.push((
+                        field.clone(),
+                        format!(
+                            "{:?} isn't a key or mouse button, use a single character, one of {} or Mouse0 to Mouse2",
+                            input,
+                            NAMED_KEYS.join(", ")
+                        ),
+                    ));
+                } else if *action == GameAction::CameraLook
+                    && !MODIFIER_KEYS.contains(&input.as_str())
+                    && !MOUSE_BUTTONS.contains(&input.as_str())
+                {
+                    issues.push((field.clone(), format!("{:?} can't be held while moving the mouse, use Shift, Control, Alt or a mouse button", input)));
+                } else if let Some(other) = GameAction::ALL
+                    .into_iter()
+                    .find(|other| other != action && self.inputs(*other).contains(input))
+                {
+                    issues.push((field.clone(), format!("{:?} is already bound to {}", input, other.label())));
+                }
+            }
+        }
+
+        issues
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CameraMode {
+    FirstPerson,
+    #[default]
+    ThirdPerson,
+    TopDown,
+    Fixed,
+}
+
+/// How the game camera follows the player in play mode
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CameraSettings {
+    pub mode: CameraMode,
+    // behind the player in third person
+    pub distance: f32,
+    // above the player in third person and top-down
+    pub height: f32,
+    // 0 sticks to the player, towards 1 trails further behind
+    pub lag: f32,
+    // where a fixed camera stands and looks at
+    pub position: Option<[f32; 3]>,
+    pub target: Option<[f32; 3]>,
+    pub fov_degrees: f32,
+    pub near: f32,
+    pub far: f32,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        CameraSettings {
+            mode: CameraMode::ThirdPerson,
+            distance: 6.0,
+            height: 2.5,
+            lag: 0.1,
+            position: None,
+            target: None,
+            fov_degrees: 60.0,
+            near: 0.1,
+            far: 1000.0,
+        }
+    }
+}
+
+impl CameraSettings {
+    /// Everything wrong with the settings as a whole, as the field and what's wrong with it
+    pub fn issues(&self) -> Vec<(&'static str, String)> {
+        let mut issues = Vec::new();
+
+        if !(self.distance.is_finite() && self.distance > 0.0) {
+            issues.push(("distance", "must be above 0".to_string()));
+        }
+        if !self.height.is_finite() {
+            issues.push(("height", "must be a finite number".to_string()));
+        }
+        if !(0.0..1.0).contains(&self.lag) {
+            issues.push(("lag", "must be at least 0 and below 1".to_string()));
+        }
+        if !(10.0..=170.0).contains(&self.fov_degrees) {
+            issues.push(("fovDegrees", "must be between 10 and 170".to_string()));
+        }
+        if !(self.near.is_finite() && self.near > 0.0) {
+            issues.push(("near", "must be above 0".to_string()));
+        }
+        if !(self.far.is_finite() && self.far > self.near) {
+            issues.push(("far", format!("must be beyond the near plane {}", self.near)));
+        }
+        if self.mode == CameraMode::Fixed {
+            match (self.position, self.target) {
+                (None, _) => issues.push(("position", "is required for a fixed camera".to_string())),
+                (_, None) => issues.push(("target", "is required for a fixed camera".to_string())),
+                (Some(position), Some(target)) if position == target => {
+                    issues.push(("target", "must be somewhere other than the camera's position".to_string()))
+                }
+                _ => {}
+            }
+        }
+
+        issues
+    }
+}
+
+/// A model imported into the asset directory for this project
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModelAsset {
+    pub name: String,
+    // every file belonging to the model, the model itself first
+    pub files: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bound(bindings: &[(GameAction, &[&str])]) -> InputMap {
+        InputMap {
+            bindings: bindings
+                .iter()
+                .map(|(action, inputs)| (*action, inputs.iter().map(|i| i.to_string()).collect()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn default_keys_reach_the_engine() {
+        let map = InputMap::default();
+        assert_eq!(map.engine_key("w").as_deref(), Some("w"));
+        assert_eq!(map.engine_key("W").as_deref(), Some("w"));
+        assert_eq!(map.engine_key(" ").as_deref(), Some(" "));
+        // not an action, passed through as is
+        assert_eq!(map.engine_key("q").as_deref(), Some("q"));
+    }
+
+    #[test]
+    fn rebound_keys_become_the_engine_key() {
+        let map = bound(&[(GameAction::MoveForward, &["ArrowUp"]), (GameAction::Jump, &["j"])]);
+        assert_eq!(map.engine_key("ArrowUp").as_deref(), Some("w"));
+        assert_eq!(map.engine_key("j").as_deref(), Some(" "));
+        // the defaults they replaced no longer reach the engine
+        assert_eq!(map.engine_key("w"), None);
+        assert_eq!(map.engine_key(" "), None);
+    }
+
+    #[test]
+    fn camera_look_and_unbound_mouse_buttons_are_dropped() {
+        let map = bound(&[(GameAction::CameraLook, &["Mouse2"])]);
+        assert_eq!(map.engine_key("Mouse2"), None);
+        assert_eq!(map.engine_key("Mouse0"), None);
+        assert_eq!(map.engine_key("Shift"), None);
+        assert_eq!(InputMap::default().engine_key("Shift"), None);
+    }
+
+    #[test]
+    fn bound_mouse_buttons_become_their_action_key() {
+        let map = bound(&[(GameAction::Attack, &["Mouse0"]), (GameAction::Jump, &["Mouse2"])]);
+        assert_eq!(map.engine_key("Mouse0").as_deref(), Some("f"));
+        assert_eq!(map.engine_key("Mouse2").as_deref(), Some(" "));
+    }
+
+    #[test]
+    fn default_bindings_have_no_issues() {
+        assert!(InputMap::default().issues().is_empty());
+    }
+
+    #[test]
+    fn reports_bad_bindings() {
+        let map = bound(&[
+            (GameAction::MoveForward, &[]),
+            (GameAction::Jump, &["Spacebar"]),
+            (GameAction::Attack, &["e"]),
+            (GameAction::CameraLook, &["x"]),
+        ]);
+        let issues = map.issues();
+        let fields: Vec<&str> = issues.iter().map(|(field, _)| field.as_str()).collect();
+
+        assert_eq!(fields, ["moveForward", "jump", "attack", "cameraLook"]);
+        assert!(issues[2].1.contains("Interact"));
+    }
+
+    #[test]
+    fn default_camera_has_no_issues() {
+        assert!(CameraSettings::default().issues().is_empty());
+    }
+
+    #[test]
+    fn reports_out_of_range_camera_settings() {
+        let camera = CameraSettings {
+            distance: 0.0,
+            height: f32::NAN,
+            lag: 1.0,
+            fov_degrees: 5.0,
+            near: 10.0,
+            far: 10.0,
+            ..CameraSettings::default()
+        };
+        let fields: Vec<&str> = camera.issues().iter().map(|(field, _)| *field).collect();
+
+        assert_eq!(fields, ["distance", "height", "lag", "fovDegrees", "far"]);
+    }
+
+    #[test]
+    fn fixed_camera_needs_a_position_and_a_distinct_target() {
+        let mut camera = CameraSettings {
+            mode: CameraMode::Fixed,
+            ..CameraSettings::default()
+        };
+        assert_eq!(camera.issues()[0].0, "position");
+
+        camera.position = Some([0.0, 10.0, 0.0]);
+        assert_eq!(camera.issues()[0].0, "target");
+
+        camera.target = camera.position;
+        assert_eq!(camera.issues()[0].0, "target");
+
+        camera.target = Some([0.0, 0.0, 0.0]);
+        assert!(camera.issues().is_empty());
+    }
+}
@@ -0,0 +1,481 @@
+use entropy_engine::core::pipeline::ExportPipeline;
+use entropy_engine::helpers::saved_data::{ComponentData, ComponentKind};
+use leptos::logging::log;
+use leptos::prelude::*;
+
+use crate::app::ProjectInfo;
+use crate::extras::ProjectExtras;
+use crate::scene::reload_scene;
+use crate::tools::{water_plane_index, ToolContext};
+
+// enough to walk back a long chat session without holding on to every snapshot forever
+const MAX_HISTORY: usize = 100;
+
+/// One component as it was before and after a change, `None` meaning it didn't exist
+#[derive(Clone, Debug)]
+pub struct ComponentChange {
+    pub component_id: String,
+    // index of the level holding it, so a removal is undone into the same level
+    pub level: usize,
+    pub before: Option<ComponentData>,
+    pub after: Option<ComponentData>,
+}
+
+/// Everything one tool call or property edit changed, undone and redone as a unit
+#[derive(Clone, Debug)]
+pub struct HistoryEntry {
+    pub label: String,
+    pub changes: Vec<ComponentChange>,
+    // before and after, only when the call touched them
+    pub extras: Option<(ProjectExtras, ProjectExtras)>,
+}
+
+/// The parts of a scene a change can touch
+#[derive(Clone, Debug)]
+pub struct SceneSnapshot {
+    // each with the index of its level
+    pub components: Vec<(usize, ComponentData)>,
+    pub extras: ProjectExtras,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    undo_stack: Vec<HistoryEntry>,
+    redo_stack: Vec<HistoryEntry>,
+    // bumped by every change, including undo and redo, to tell whether disk is behind
+    revision: u64,
+    saved_revision: u64,
+}
+
+impl History {
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.revision != self.saved_revision
+    }
+
+    /// Marks the scene as saved up to `revision`, later changes stay dirty
+    pub fn mark_saved(&mut self, revision: u64) {
+        self.saved_revision = self.saved_revision.max(revision);
+    }
+
+    pub fn push(&mut self, entry: HistoryEntry) {
+        self.revision += 1;
+        self.undo_stack.push(entry);
+        if self.undo_stack.len() > MAX_HISTORY {
+            self.undo_stack.remove(0);
+        }
+        self.redo_stack.clear();
+    }
+
+    pub fn undo_label(&self) -> Option<String> {
+        self.undo_stack.last().map(|e| e.label.clone())
+    }
+
+    pub fn redo_label(&self) -> Option<String> {
+        self.redo_stack.last().map(|e| e.label.clone())
+    }
+
+    fn take_undo(&mut self) -> Option<HistoryEntry> {
+        let entry = self.undo_stack.pop()?;
+        self.revision += 1;
+        self.redo_stack.push(entry.clone());
+        Some(entry)
+    }
+
+    fn take_redo(&mut self) -> Option<HistoryEntry> {
+        let entry = self.redo_stack.pop()?;
+        self.revision += 1;
+        self.undo_stack.push(entry.clone());
+        Some(entry)
+    }
+}
+
+/// The handles a property panel needs to make an edit that can be undone
+#[derive(Clone, Copy)]
+pub struct EditScope {
+    pub pipeline_store: LocalResource<Option<std::rc::Rc<std::cell::RefCell<ExportPipeline>>>>,
+    pub selected_project: ReadSignal<Option<ProjectInfo>>,
+    pub set_state_revision: WriteSignal<u32>,
+    pub history: RwSignal<History>,
+    pub extras: RwSignal<ProjectExtras>,
+}
+
+impl EditScope {
+    pub fn context(&self) -> Option<ToolContext> {
+        let project = self.selected_project.get_untracked()?;
+        Some(ToolContext {
+            pipeline_store: self.pipeline_store,
+            project_id: project.id,
+            set_state_revision: self.set_state_revision,
+            history: self.history,
+            extras: self.extras,
+        })
+    }
+}
+
+pub fn snapshot(ctx: &ToolContext) -> SceneSnapshot {
+    SceneSnapshot {
+        components: snapshot_components(ctx),
+        extras: ctx.extras.get_untracked(),
+    }
+}
+
+/// Every component in every level, as the editor currently has it
+fn snapshot_components(ctx: &ToolContext) -> Vec<(usize, ComponentData)> {
+    let Some(pipeline_arc) = ctx.pipeline() else {
+        return Vec::new();
+    };
+    let pipeline = pipeline_arc.borrow();
+
+    pipeline
+        .export_editor
+        .as_ref()
+        .and_then(|e| e.saved_state.as_ref())
+        .and_then(|s| s.levels.as_ref())
+        .map(|levels| {
+            levels
+                .iter()
+                .enumerate()
+                .filter_map(|(index, l)| l.components.as_ref().map(|components| (index, components)))
+                .flat_map(|(index, components)| components.iter().map(move |c| (index, c.clone())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// components don't implement PartialEq, their serialized form is what gets saved anyway
+fn same_component(a: &ComponentData, b: &ComponentData) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+pub fn diff_components(before: &[(usize, ComponentData)], after: &[(usize, ComponentData)]) -> Vec<ComponentChange> {
+    let mut changes = Vec::new();
+
+    for (level, old) in before {
+        match after.iter().find(|(_, c)| c.id == old.id) {
+            Some((_, new)) if same_component(old, new) => {}
+            new => changes.push(ComponentChange {
+                component_id: old.id.clone(),
+                level: *level,
+                before: Some(old.clone()),
+                after: new.map(|(_, c)| c.clone()),
+            }),
+        }
+    }
+
+    for (level, new) in after {
+        if !before.iter().any(|(_, c)| c.id == new.id) {
+            changes.push(ComponentChange {
+                component_id: new.id.clone(),
+                level: *level,
+                before: None,
+                after: Some(new.clone()),
+            });
+        }
+    }
+
+    changes
+}
+
+/// Records whatever changed between two snapshots, doing nothing if nothing did
+pub fn record(ctx: &ToolContext, label: impl Into<String>, before: &SceneSnapshot, after: &SceneSnapshot) {
+    let changes = diff_components(&before.components, &after.components);
+    let extras = (before.extras != after.extras).then(|| (before.extras.clone(), after.extras.clone()));
+    if changes.is_empty() && extras.is_none() {
+        return;
+    }
+
+    ctx.history.update(|history| {
+        history.push(HistoryEntry {
+            label: label.into(),
+            changes,
+            extras,
+        })
+    });
+}
+
+/// Applies an edit from the property editor to the scene and records it
+pub async fn edit_component(
+    ctx: &ToolContext,
+    label: impl Into<String>,
+    component_id: &str,
+    edit: impl FnOnce(&mut ComponentData),
+) {
+    let before = snapshot(ctx);
+    let Some((level, mut component)) = before.components.iter().find(|(_, c)| c.id == component_id).cloned() else {
+        log!("Can't edit missing component {:?}", component_id);
+        return;
+    };
+    edit(&mut component);
+
+    apply_states(ctx, &[(component_id.to_string(), level, Some(component))], None).await;
+
+    let after = snapshot(ctx);
+    record(ctx, label, &before, &after);
+}
+
+/// Applies an edit to the project extras from a property panel and records it
+pub async fn edit_extras(ctx: &ToolContext, label: impl Into<String>, edit: impl FnOnce(&mut ProjectExtras)) {
+    let before = snapshot(ctx);
+    let mut extras = before.extras.clone();
+    edit(&mut extras);
+    if extras == before.extras {
+        return;
+    }
+
+    apply_states(ctx, &[], Some(extras)).await;
+
+    let after = snapshot(ctx);
+    record(ctx, label, &before, &after);
+}
+
+pub async fn undo(ctx: &ToolContext) {
+    let Some(entry) = ctx.history.try_update(|history| history.take_undo()).flatten() else {
+        return;
+    };
+    log!("Undoing {:?}", entry.label);
+
+    let states: Vec<_> = entry
+        .changes
+        .iter()
+        .map(|change| (change.component_id.clone(), change.level, change.before.clone()))
+        .collect();
+    apply_states(ctx, &states, entry.extras.map(|(before, _)| before)).await;
+}
+
+pub async fn redo(ctx: &ToolContext) {
+    let Some(entry) = ctx.history.try_update(|history| history.take_redo()).flatten() else {
+        return;
+    };
+    log!("Redoing {:?}", entry.label);
+
+    let states: Vec<_> = entry
+        .changes
+        .iter()
+        .map(|change| (change.component_id.clone(), change.level, change.after.clone()))
+        .collect();
+    apply_states(ctx, &states, entry.extras.map(|(_, after)| after)).await;
+}
+
+/// Whether the renderer can show `new` in place of `old` without placing the scene again: model
+/// transforms and water configs are updated live, names aren't rendered at all
+fn updates_in_place(old: &ComponentData, new: &ComponentData) -> bool {
+    let (Ok(mut old_value), Ok(mut new_value)) = (serde_json::to_value(old), serde_json::to_value(new)) else {
+        return false;
+    };
+
+    let mut live_fields = vec!["/generic_properties/name"];
+    match new.kind {
+        Some(ComponentKind::Model) => live_fields.extend([
+            "/generic_properties/position",
+            "/generic_properties/rotation",
+            "/generic_properties/scale",
+        ]),
+        Some(ComponentKind::WaterPlane) => live_fields.push("/water_properties"),
+        _ => {}
+    }
+    for field in live_fields {
+        for value in [&mut old_value, &mut new_value] {
+            if let Some(field) = value.pointer_mut(field) {
+                *field = serde_json::Value::Null;
+            }
+        }
+    }
+
+    old_value == new_value
+}
+
+/// Puts components into the given states in both the saved state and the renderer, the autosave writing them out
+async fn apply_states(ctx: &ToolContext, states: &[(String, usize, Option<ComponentData>)], extras: Option<ProjectExtras>) {
+    // changes the renderer can't make to a running scene place it again from memory
+    let mut needs_reload = false;
+
+    if let Some(extras) = extras {
+        ctx.extras.set(extras);
+    }
+
+    if let Some(pipeline_arc) = ctx.pipeline() {
+        let mut pipeline = pipeline_arc.borrow_mut();
+        if let Some(editor) = pipeline.export_editor.as_mut() {
+            for (component_id, level_index, state) in states {
+                let old_water_index = editor
+                    .saved_state
+                    .as_ref()
+                    .and_then(|saved_state| water_plane_index(saved_state, component_id));
+
+                let mut old_component = None;
+                if let Some(saved_state) = editor.saved_state.as_mut() {
+                    let levels = saved_state.levels.get_or_insert_with(Vec::new);
+
+                    for level in levels.iter_mut() {
+                        let Some(components) = level.components.as_mut() else {
+                            continue;
+                        };
+                        if let Some(index) = components.iter().position(|c| &c.id == component_id) {
+                            old_component = Some(match state {
+                                Some(component) => std::mem::replace(&mut components[index], component.clone()),
+                                None => components.remove(index),
+                            });
+                            break;
+                        }
+                    }
+
+                    if old_component.is_none() {
+                        if let Some(component) = state {
+                            // back into the level it was removed from
+                            let index = if *level_index < levels.len() { *level_index } else { 0 };
+                            if let Some(level) = levels.get_mut(index) {
+                                level.components.get_or_insert_with(Vec::new).push(component.clone());
+                            }
+                        }
+                    }
+                }
+
+                let in_place = match (&old_component, state) {
+                    (Some(old), Some(new)) => updates_in_place(old, new),
+                    // models and water planes can be dropped from the renderer directly
+                    (Some(old), None) => matches!(old.kind, Some(ComponentKind::Model) | Some(ComponentKind::WaterPlane)),
+                    (None, Some(_)) => false,
+                    (None, None) => true,
+                };
+                if !in_place {
+                    needs_reload = true;
+                    continue;
+                }
+
+                let water_index = editor
+                    .saved_state
+                    .as_ref()
+                    .and_then(|saved_state| water_plane_index(saved_state, component_id));
+
+                let Some(renderer_state) = editor.renderer_state.as_mut() else {
+                    continue;
+                };
+
+                match state {
+                    Some(component) => {
+                        if let Some(model) = renderer_state.models.iter_mut().find(|m| &m.id == component_id) {
+                            for mesh in model.meshes.iter_mut() {
+                                mesh.transform.update_position(component.generic_properties.position);
+                                mesh.transform.update_rotation(component.generic_properties.rotation);
+                                mesh.transform.update_scale(component.generic_properties.scale);
+                            }
+                        }
+
+                        if let (Some(index), Some(config)) = (water_index, component.water_properties) {
+                            if let (Some(water_plane), Some(gpu_resources)) =
+                                (renderer_state.water_planes.get_mut(index), editor.gpu_resources.as_ref())
+                            {
+                                water_plane.update_config(&gpu_resources.queue, config);
+                            }
+                        }
+                    }
+                    None => {
+                        renderer_state.models.retain(|m| &m.id != component_id);
+                        if let Some(index) = old_water_index.filter(|i| *i < renderer_state.water_planes.len()) {
+                            renderer_state.water_planes.remove(index);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if needs_reload {
+        if let Err(e) = reload_scene(ctx).await {
+            log!("Couldn't rebuild the scene {:?}", e);
+        }
+    }
+
+    ctx.set_state_revision.update(|revision| *revision += 1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn model(id: &str, position: [f32; 3]) -> ComponentData {
+        serde_json::from_value(json!({
+            "id": id,
+            "kind": "Model",
+            "asset_id": "models/tree.glb",
+            "generic_properties": {
+                "name": id,
+                "position": position,
+                "rotation": [0.0, 0.0, 0.0],
+                "scale": [1.0, 1.0, 1.0],
+            },
+        }))
+        .expect("test component should deserialize")
+    }
+
+    fn summary(changes: &[ComponentChange]) -> Vec<(&str, bool, bool)> {
+        changes
+            .iter()
+            .map(|c| (c.component_id.as_str(), c.before.is_some(), c.after.is_some()))
+            .collect()
+    }
+
+    fn in_level(level: usize, components: Vec<ComponentData>) -> Vec<(usize, ComponentData)> {
+        components.into_iter().map(|c| (level, c)).collect()
+    }
+
+    #[test]
+    fn unchanged_components_produce_no_changes() {
+        let components = in_level(0, vec![model("a", [0.0, 0.0, 0.0]), model("b", [1.0, 0.0, 0.0])]);
+        assert!(diff_components(&components, &components.clone()).is_empty());
+    }
+
+    #[test]
+    fn reports_edits_removals_and_additions() {
+        let before = in_level(0, vec![model("edited", [0.0, 0.0, 0.0]), model("removed", [0.0, 0.0, 0.0]), model("kept", [2.0, 0.0, 0.0])]);
+        let after = in_level(0, vec![model("kept", [2.0, 0.0, 0.0]), model("edited", [5.0, 0.0, 0.0]), model("added", [0.0, 0.0, 0.0])]);
+
+        let changes = diff_components(&before, &after);
+        assert_eq!(summary(&changes), [
+            ("edited", true, true),
+            ("removed", true, false),
+            ("added", false, true),
+        ]);
+        assert_eq!(changes[0].after.as_ref().unwrap().generic_properties.position, [5.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn reordering_is_not_a_change() {
+        let before = in_level(0, vec![model("a", [0.0, 0.0, 0.0]), model("b", [1.0, 0.0, 0.0])]);
+        let after = in_level(0, vec![model("b", [1.0, 0.0, 0.0]), model("a", [0.0, 0.0, 0.0])]);
+
+        assert!(diff_components(&before, &after).is_empty());
+    }
+
+    #[test]
+    fn changes_keep_the_level_of_their_component() {
+        let mut before = in_level(0, vec![model("a", [0.0, 0.0, 0.0])]);
+        before.extend(in_level(2, vec![model("removed", [0.0, 0.0, 0.0])]));
+        let mut after = in_level(0, vec![model("a", [0.0, 0.0, 0.0])]);
+        after.extend(in_level(1, vec![model("added", [0.0, 0.0, 0.0])]));
+
+        let levels: Vec<(&str, usize)> = diff_components(&before, &after)
+            .iter()
+            .map(|c| (c.component_id.as_str(), c.level))
+            .collect();
+        assert_eq!(levels, [("removed", 2), ("added", 1)]);
+    }
+
+    #[test]
+    fn only_model_transforms_and_names_update_in_place() {
+        let old = model("a", [0.0, 0.0, 0.0]);
+
+        let mut moved = model("a", [4.0, 0.0, 0.0]);
+        moved.generic_properties.name = "Renamed".to_string();
+        assert!(updates_in_place(&old, &moved));
+
+        let mut swapped = old.clone();
+        swapped.asset_id = "models/rock.glb".to_string();
+        assert!(!updates_in_place(&old, &swapped));
+    }
+}
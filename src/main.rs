@@ -1,4 +1,9 @@
 mod app;
+mod errors;
+mod extras;
+mod history;
+mod scene;
+mod tools;
 mod components;
 
 use app::*;
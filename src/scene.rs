@@ -0,0 +1,118 @@
+use entropy_engine::core::pipeline::ExportPipeline;
+use entropy_engine::grass::config::GrassConfig;
+use entropy_engine::helpers::load_project::{load_project, place_project};
+use entropy_engine::helpers::saved_data::SavedState;
+use leptos::logging::log;
+use leptos::prelude::*;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::app::invoke_command;
+use crate::errors::CommandError;
+use crate::extras::ProjectExtras;
+use crate::tools::ToolContext;
+
+/// Writes the editor's saved state to disk as it is right now
+pub async fn save_scene(ctx: &ToolContext) -> Result<(), CommandError> {
+    let saved_state = ctx.pipeline().and_then(|pipeline_arc| {
+        let pipeline = pipeline_arc.borrow();
+        pipeline.export_editor.as_ref().and_then(|e| e.saved_state.clone())
+    });
+    let Some(saved_state) = saved_state else {
+        return Err(CommandError::Unknown {
+            message: "The project isn't loaded".to_string(),
+        });
+    };
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct SaveProjectArgs {
+        project_id: String,
+        saved_state: SavedState,
+        extras: ProjectExtras,
+    }
+
+    invoke_command("save_project", &SaveProjectArgs {
+        project_id: ctx.project_id.clone(),
+        saved_state,
+        extras: ctx.extras.get_untracked(),
+    }).await
+}
+
+/// Places the project into the editor as it is on disk. The editor is taken out of the pipeline meanwhile,
+/// so the `RefCell` is never borrowed across the await and other borrowers just see no editor
+pub async fn place_scene(pipeline_arc: &Rc<RefCell<ExportPipeline>>, project_id: &str) {
+    let Some(mut editor) = pipeline_arc.borrow_mut().export_editor.take() else {
+        return;
+    };
+
+    log!("Placing scene for {:?}", project_id);
+    load_project(&mut editor, project_id).await;
+
+    pipeline_arc.borrow_mut().export_editor = Some(editor);
+}
+
+/// Places the editor's saved state again as it is in memory, for changes the renderer can't make to
+/// a running scene. Nothing is written, the autosave picks the change up like any other edit
+pub async fn reload_scene(ctx: &ToolContext) -> Result<(), CommandError> {
+    let Some(pipeline_arc) = ctx.pipeline() else {
+        return Err(CommandError::Unknown {
+            message: "The project isn't loaded".to_string(),
+        });
+    };
+
+    let Some(mut editor) = pipeline_arc.borrow_mut().export_editor.take() else {
+        return Err(CommandError::Unknown {
+            message: "The project isn't loaded".to_string(),
+        });
+    };
+    let Some(saved_state) = editor.saved_state.clone() else {
+        pipeline_arc.borrow_mut().export_editor = Some(editor);
+        return Err(CommandError::Unknown {
+            message: "The project isn't loaded".to_string(),
+        });
+    };
+
+    log!("Placing scene for {:?} from memory", ctx.project_id);
+    place_project(&mut editor, &ctx.project_id, saved_state).await;
+
+    pipeline_arc.borrow_mut().export_editor = Some(editor);
+
+    ctx.set_state_revision.update(|revision| *revision += 1);
+
+    Ok(())
+}
+
+/// Puts the settings `SavedState` has no fields for onto the running scene. Placing the project builds
+/// the scene from the saved state alone, so this runs again after every placement and edit
+pub fn apply_extras(pipeline_arc: &Rc<RefCell<ExportPipeline>>, extras: &ProjectExtras) {
+    let mut pipeline = pipeline_arc.borrow_mut();
+    let Some(editor) = pipeline.export_editor.as_mut() else {
+        return;
+    };
+
+    // the lens is shared with the viewport, following the player is left to play mode
+    if let (Some(settings), Some(camera)) = (extras.camera.as_ref(), editor.camera.as_mut()) {
+        camera.fovy = settings.fov_degrees.to_radians();
+        camera.znear = settings.near;
+        camera.zfar = settings.far;
+    }
+
+    let (Some(renderer_state), Some(gpu_resources)) = (editor.renderer_state.as_mut(), editor.gpu_resources.as_ref()) else {
+        return;
+    };
+
+    for grass in renderer_state.grasses.iter_mut() {
+        let Some(settings) = extras.grass.get(&grass.landscape_id) else {
+            continue;
+        };
+        grass.update_config(&gpu_resources.queue, GrassConfig {
+            blade_height: settings.blade_height,
+            blade_color: settings.blade_color,
+            wind_strength: settings.wind_strength,
+            wind_speed: settings.wind_speed,
+            wind_direction: settings.wind_direction,
+        });
+    }
+}
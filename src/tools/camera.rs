@@ -0,0 +1,128 @@
+use leptos::logging::log;
+use leptos::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::extras::CameraMode;
+
+use super::validate::{parse_arguments, Validator};
+use super::{ToolContext, ToolFuture, ToolHandler};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ConfigureCameraArgs {
+    mode: Option<CameraMode>,
+    distance: Option<f32>,
+    height: Option<f32>,
+    lag: Option<f32>,
+    position: Option<[f32; 3]>,
+    target: Option<[f32; 3]>,
+    fov_degrees: Option<f32>,
+    near: Option<f32>,
+    far: Option<f32>,
+}
+
+pub struct ConfigureCamera;
+
+impl ToolHandler for ConfigureCamera {
+    fn name(&self) -> &'static str {
+        "configureCamera"
+    }
+
+    fn description(&self) -> &'static str {
+        "Set the game camera used in play mode: first person, third person follow, top-down or fixed, its field of view and clipping planes"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        let vec3 = |description: &str| {
+            json!({
+                "type": "array",
+                "items": { "type": "number" },
+                "minItems": 3,
+                "maxItems": 3,
+                "description": description,
+            })
+        };
+
+        json!({
+            "type": "object",
+            "properties": {
+                "mode": { "type": "string", "enum": ["firstPerson", "thirdPerson", "topDown", "fixed"] },
+                "distance": {
+                    "type": "number",
+                    "exclusiveMinimum": 0,
+                    "description": "How far behind the player a third person camera follows",
+                },
+                "height": { "type": "number", "description": "How high above the player a third person or top-down camera sits" },
+                "lag": {
+                    "type": "number",
+                    "minimum": 0,
+                    "exclusiveMaximum": 1,
+                    "description": "How far a third person camera trails behind, 0 sticks to the player",
+                },
+                "position": vec3("Where a fixed camera stands"),
+                "target": vec3("What a fixed camera looks at"),
+                "fovDegrees": { "type": "number", "minimum": 10, "maximum": 170 },
+                "near": { "type": "number", "exclusiveMinimum": 0 },
+                "far": { "type": "number", "exclusiveMinimum": 0 },
+            },
+            "additionalProperties": false,
+        })
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a> {
+        Box::pin(async move {
+            let args: ConfigureCameraArgs = parse_arguments(arguments)?;
+
+            let mut settings = ctx.extras.with_untracked(|extras| extras.camera.clone()).unwrap_or_default();
+            if let Some(mode) = args.mode {
+                settings.mode = mode;
+            }
+            if let Some(distance) = args.distance {
+                settings.distance = distance;
+            }
+            if let Some(height) = args.height {
+                settings.height = height;
+            }
+            if let Some(lag) = args.lag {
+                settings.lag = lag;
+            }
+            if let Some(position) = args.position {
+                settings.position = Some(position);
+            }
+            if let Some(target) = args.target {
+                settings.target = Some(target);
+            }
+            if let Some(fov_degrees) = args.fov_degrees {
+                settings.fov_degrees = fov_degrees;
+            }
+            if let Some(near) = args.near {
+                settings.near = near;
+            }
+            if let Some(far) = args.far {
+                settings.far = far;
+            }
+
+            let mut validator = Validator::new();
+            if let Some(position) = args.position {
+                validator.finite("position", &position);
+            }
+            if let Some(target) = args.target {
+                validator.finite("target", &target);
+            }
+            // checked on the merged settings, a fixed camera may have been placed by an earlier call
+            for (field, message) in settings.issues() {
+                validator.issue(field, message);
+            }
+            validator.finish()?;
+
+            ctx.extras.update(|extras| {
+                extras.camera = Some(settings.clone());
+            });
+
+            log!("Configured camera {:?}", settings);
+
+            Ok(json!({ "camera": settings }))
+        })
+    }
+}
@@ -0,0 +1,351 @@
+use entropy_engine::helpers::saved_data::{ComponentKind, SavedState};
+use entropy_engine::water_plane::config::WaterConfig;
+use leptos::logging::log;
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::lookup::{component_mut, components, resolve_component};
+use super::validate::{parse_arguments, Validator};
+use super::{ToolContext, ToolError, ToolFuture, ToolHandler};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct ConfigureWaterArgs {
+    #[serde(rename = "componentId")]
+    pub(super) component_id: Option<String>,
+    shallow_color: Option<[f32; 3]>,
+    medium_color: Option<[f32; 3]>,
+    deep_color: Option<[f32; 3]>,
+    ripple_amplitude_multiplier: Option<f32>,
+    ripple_freq: Option<f32>,
+    ripple_speed: Option<f32>,
+    shoreline_foam_range: Option<f32>,
+    crest_foam_min: Option<f32>,
+    crest_foam_max: Option<f32>,
+    sparkle_intensity: Option<f32>,
+    sparkle_threshold: Option<f32>,
+    subsurface_multiplier: Option<f32>,
+    fresnel_power: Option<f32>,
+    fresnel_multiplier: Option<f32>,
+
+    // Wave 1 - primary wave
+    pub wave1_amplitude: Option<f32>,
+    pub wave1_frequency: Option<f32>,
+    pub wave1_speed: Option<f32>,
+    pub wave1_steepness: Option<f32>,
+    pub wave1_direction: Option<[f32; 2]>,
+
+    // Wave 2 - secondary wave
+    pub wave2_amplitude: Option<f32>,
+    pub wave2_frequency: Option<f32>,
+    pub wave2_speed: Option<f32>,
+    pub wave2_steepness: Option<f32>,
+    pub wave2_direction: Option<[f32; 2]>,
+
+    // Wave 3 - tertiary wave
+    pub wave3_amplitude: Option<f32>,
+    pub wave3_frequency: Option<f32>,
+    pub wave3_speed: Option<f32>,
+    pub wave3_steepness: Option<f32>,
+    pub wave3_direction: Option<[f32; 2]>,
+}
+
+impl ConfigureWaterArgs {
+    /// Checks only the values that were given, the merged config is checked again when it is saved
+    pub(super) fn validate(&self) -> Result<(), ToolError> {
+        let mut validator = Validator::new();
+
+        let colors = [
+            ("shallow_color", self.shallow_color),
+            ("medium_color", self.medium_color),
+            ("deep_color", self.deep_color),
+        ];
+        for (name, color) in colors {
+            if let Some(color) = color {
+                validator.unit_range(name, &color);
+            }
+        }
+
+        let non_negative = [
+            ("ripple_amplitude_multiplier", self.ripple_amplitude_multiplier),
+            ("ripple_freq", self.ripple_freq),
+            ("shoreline_foam_range", self.shoreline_foam_range),
+            ("sparkle_intensity", self.sparkle_intensity),
+            ("subsurface_multiplier", self.subsurface_multiplier),
+            ("fresnel_power", self.fresnel_power),
+            ("fresnel_multiplier", self.fresnel_multiplier),
+            ("wave1_amplitude", self.wave1_amplitude),
+            ("wave1_frequency", self.wave1_frequency),
+            ("wave2_amplitude", self.wave2_amplitude),
+            ("wave2_frequency", self.wave2_frequency),
+            ("wave3_amplitude", self.wave3_amplitude),
+            ("wave3_frequency", self.wave3_frequency),
+        ];
+        for (name, value) in non_negative {
+            if let Some(value) = value {
+                validator.non_negative(name, &[value]);
+            }
+        }
+
+        let finite = [
+            ("ripple_speed", self.ripple_speed),
+            ("crest_foam_min", self.crest_foam_min),
+            ("crest_foam_max", self.crest_foam_max),
+            ("sparkle_threshold", self.sparkle_threshold),
+            ("wave1_speed", self.wave1_speed),
+            ("wave1_steepness", self.wave1_steepness),
+            ("wave2_speed", self.wave2_speed),
+            ("wave2_steepness", self.wave2_steepness),
+            ("wave3_speed", self.wave3_speed),
+            ("wave3_steepness", self.wave3_steepness),
+        ];
+        for (name, value) in finite {
+            if let Some(value) = value {
+                validator.finite(name, &[value]);
+            }
+        }
+
+        let directions = [
+            ("wave1_direction", self.wave1_direction),
+            ("wave2_direction", self.wave2_direction),
+            ("wave3_direction", self.wave3_direction),
+        ];
+        for (name, direction) in directions {
+            if let Some(direction) = direction {
+                validator.finite(name, &direction);
+            }
+        }
+
+        if let (Some(min), Some(max)) = (self.crest_foam_min, self.crest_foam_max) {
+            if min > max {
+                validator.issue("crest_foam_min", "must not be greater than crest_foam_max");
+            }
+        }
+
+        validator.finish()
+    }
+
+    pub(super) fn apply(&self, config: &mut WaterConfig) {
+        if let Some(color) = self.shallow_color {
+            config.shallow_color = [color[0], color[1], color[2], 1.0];
+        }
+        if let Some(color) = self.medium_color {
+            config.medium_color = [color[0], color[1], color[2], 1.0];
+        }
+        if let Some(color) = self.deep_color {
+            config.deep_color = [color[0], color[1], color[2], 1.0];
+        }
+        if let Some(val) = self.ripple_amplitude_multiplier {
+            config.ripple_amplitude_multiplier = val;
+        }
+        if let Some(val) = self.ripple_freq {
+            config.ripple_freq = val;
+        }
+        if let Some(val) = self.ripple_speed {
+            config.ripple_speed = val;
+        }
+        if let Some(val) = self.shoreline_foam_range {
+            config.shoreline_foam_range = val;
+        }
+        if let Some(val) = self.crest_foam_min {
+            config.crest_foam_min = val;
+        }
+        if let Some(val) = self.crest_foam_max {
+            config.crest_foam_max = val;
+        }
+        if let Some(val) = self.sparkle_intensity {
+            config.sparkle_intensity = val;
+        }
+        if let Some(val) = self.sparkle_threshold {
+            config.sparkle_threshold = val;
+        }
+        if let Some(val) = self.subsurface_multiplier {
+            config.subsurface_multiplier = val;
+        }
+        if let Some(val) = self.fresnel_power {
+            config.fresnel_power = val;
+        }
+        if let Some(val) = self.fresnel_multiplier {
+            config.fresnel_multiplier = val;
+        }
+
+        if let Some(val) = self.wave1_amplitude {
+            config.wave1_amplitude = val;
+        }
+        if let Some(val) = self.wave1_frequency {
+            config.wave1_frequency = val;
+        }
+        if let Some(val) = self.wave1_speed {
+            config.wave1_speed = val;
+        }
+        if let Some(val) = self.wave1_steepness {
+            config.wave1_steepness = val;
+        }
+        if let Some(val) = self.wave1_direction {
+            config.wave1_direction = val;
+        }
+
+        if let Some(val) = self.wave2_amplitude {
+            config.wave2_amplitude = val;
+        }
+        if let Some(val) = self.wave2_frequency {
+            config.wave2_frequency = val;
+        }
+        if let Some(val) = self.wave2_speed {
+            config.wave2_speed = val;
+        }
+        if let Some(val) = self.wave2_steepness {
+            config.wave2_steepness = val;
+        }
+        if let Some(val) = self.wave2_direction {
+            config.wave2_direction = val;
+        }
+
+        if let Some(val) = self.wave3_amplitude {
+            config.wave3_amplitude = val;
+        }
+        if let Some(val) = self.wave3_frequency {
+            config.wave3_frequency = val;
+        }
+        if let Some(val) = self.wave3_speed {
+            config.wave3_speed = val;
+        }
+        if let Some(val) = self.wave3_steepness {
+            config.wave3_steepness = val;
+        }
+        if let Some(val) = self.wave3_direction {
+            config.wave3_direction = val;
+        }
+    }
+}
+
+/// Position of a water plane component's renderer plane, since planes are created in component order
+pub fn water_plane_index(saved_state: &SavedState, component_id: &str) -> Option<usize> {
+    components(saved_state)
+        .filter(|c| matches!(c.kind, Some(ComponentKind::WaterPlane)))
+        .position(|c| c.id == component_id)
+}
+
+/// Schemas for every `WaterConfig` field a tool can set
+pub(super) fn water_config_properties() -> serde_json::Map<String, serde_json::Value> {
+    let color = json!({
+        "type": "array",
+        "items": { "type": "number", "minimum": 0, "maximum": 1 },
+        "minItems": 3,
+        "maxItems": 3,
+    });
+    let direction = json!({
+        "type": "array",
+        "items": { "type": "number" },
+        "minItems": 2,
+        "maxItems": 2,
+    });
+    let number = json!({ "type": "number" });
+
+    let mut properties = serde_json::Map::new();
+    for name in ["shallow_color", "medium_color", "deep_color"] {
+        properties.insert(name.to_string(), color.clone());
+    }
+    for name in [
+        "ripple_amplitude_multiplier", "ripple_freq", "ripple_speed", "shoreline_foam_range",
+        "crest_foam_min", "crest_foam_max", "sparkle_intensity", "sparkle_threshold",
+        "subsurface_multiplier", "fresnel_power", "fresnel_multiplier",
+    ] {
+        properties.insert(name.to_string(), number.clone());
+    }
+    for wave in 1..=3 {
+        for field in ["amplitude", "frequency", "speed", "steepness"] {
+            properties.insert(format!("wave{}_{}", wave, field), number.clone());
+        }
+        properties.insert(format!("wave{}_direction", wave), direction.clone());
+    }
+
+    properties
+}
+
+pub struct ConfigureWater;
+
+impl ToolHandler for ConfigureWater {
+    fn name(&self) -> &'static str {
+        "configureWater"
+    }
+
+    fn description(&self) -> &'static str {
+        "Change the colors, ripples, foam, lighting and waves of a water plane"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        let mut properties = water_config_properties();
+        properties.insert("componentId".to_string(), json!({
+            "type": "string",
+            "description": "Id of the WaterPlane component, may be left out when there is only one",
+        }));
+
+        json!({
+            "type": "object",
+            "properties": properties,
+            "additionalProperties": false,
+        })
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a> {
+        Box::pin(async move {
+            log!("Configuring water plane...");
+            let args: ConfigureWaterArgs = parse_arguments(arguments)?;
+            args.validate()?;
+
+            let (component_id, config) = {
+                let Some(pipeline_arc) = ctx.pipeline() else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+                let mut pipeline = pipeline_arc.borrow_mut();
+                let Some(editor) = pipeline.export_editor.as_mut() else {
+                    return Err(ToolError::failed("The editor isn't ready"));
+                };
+                let Some(saved_state) = editor.saved_state.as_mut() else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+
+                let component = resolve_component(
+                    saved_state,
+                    "componentId",
+                    args.component_id.as_deref(),
+                    "WaterPlane",
+                    |c| matches!(c.kind, Some(ComponentKind::WaterPlane)),
+                )?;
+                let Some(index) = water_plane_index(saved_state, &component.id) else {
+                    return Err(ToolError::failed(format!("Water plane {:?} isn't in the scene", component.id)));
+                };
+                let Some(water_plane) = editor.renderer_state.as_mut().and_then(|r| r.water_planes.get_mut(index)) else {
+                    return Err(ToolError::failed(format!("Water plane {:?} hasn't been created in the renderer", component.id)));
+                };
+
+                // the saved config is the source of truth, the renderer's only matters for planes saved before it existed
+                let mut config = component.water_properties.unwrap_or(water_plane.config);
+
+                log!("Configuring water plane {:?} {:?}", component.id, args);
+
+                args.apply(&mut config);
+
+                let Some(gpu_resources) = editor.gpu_resources.as_ref() else {
+                    return Err(ToolError::failed("The renderer isn't ready"));
+                };
+                water_plane.update_config(&gpu_resources.queue, config);
+
+                if let Some(saved) = component_mut(saved_state, &component.id) {
+                    saved.water_properties = Some(config);
+                }
+
+                (component.id, config)
+            };
+
+            log!("Configured water plane {:?} {:?}", component_id, config);
+
+            // recorded as an edit like any other, so the autosave writes it with the rest of the scene
+            ctx.set_state_revision.update(|revision| *revision += 1);
+
+            Ok(serde_json::Value::Null)
+        })
+    }
+}
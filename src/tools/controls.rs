@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+
+use leptos::logging::log;
+use leptos::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::extras::{normalize_input, GameAction};
+
+use super::validate::{parse_arguments, Validator};
+use super::{ToolContext, ToolFuture, ToolHandler};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ConfigureControlsArgs {
+    #[serde(default)]
+    bindings: BTreeMap<GameAction, Vec<String>>,
+    // back to the engine's defaults before applying `bindings`
+    #[serde(default)]
+    reset: bool,
+}
+
+pub struct ConfigureControls;
+
+impl ToolHandler for ConfigureControls {
+    fn name(&self) -> &'static str {
+        "configureControls"
+    }
+
+    fn description(&self) -> &'static str {
+        "Bind the game's actions (moving, jumping, attacking, interacting and looking around) to keys or mouse buttons"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        let inputs = json!({
+            "type": "array",
+            "items": { "type": "string" },
+            "minItems": 1,
+            "description": "Single characters, named keys like Space, Shift or ArrowUp, or Mouse0 (left), Mouse1 (middle) and Mouse2 (right)",
+        });
+        let properties: serde_json::Map<String, serde_json::Value> = GameAction::ALL
+            .iter()
+            .filter_map(|action| serde_json::to_value(action).ok())
+            .filter_map(|name| name.as_str().map(|name| (name.to_string(), inputs.clone())))
+            .collect();
+
+        json!({
+            "type": "object",
+            "properties": {
+                "bindings": {
+                    "type": "object",
+                    "description": "Inputs for each action to change, cameraLook must be held while moving the mouse",
+                    "properties": properties,
+                    "additionalProperties": false,
+                },
+                "reset": { "type": "boolean", "description": "Go back to the default controls first" },
+            },
+            "additionalProperties": false,
+        })
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a> {
+        Box::pin(async move {
+            let args: ConfigureControlsArgs = parse_arguments(arguments)?;
+
+            let mut controls = if args.reset {
+                Default::default()
+            } else {
+                ctx.extras.with_untracked(|extras| extras.controls.clone())
+            };
+            for (action, inputs) in &args.bindings {
+                controls
+                    .bindings
+                    .insert(*action, inputs.iter().map(|i| normalize_input(i)).collect());
+            }
+
+            let mut validator = Validator::new();
+            for (field, message) in controls.issues() {
+                validator.issue(format!("bindings.{}", field), message);
+            }
+            validator.finish()?;
+
+            ctx.extras.update(|extras| {
+                extras.controls = controls.clone();
+            });
+
+            log!("Configured controls {:?}", controls);
+
+            // read on every key press, nothing to rebuild
+            let bindings: serde_json::Map<String, serde_json::Value> = GameAction::ALL
+                .iter()
+                .filter_map(|action| {
+                    let name = serde_json::to_value(action).ok()?.as_str()?.to_string();
+                    Some((name, json!(controls.inputs(*action))))
+                })
+                .collect();
+
+            Ok(json!({ "bindings": bindings }))
+        })
+    }
+}
@@ -0,0 +1,287 @@
+use entropy_engine::helpers::saved_data::{ComponentData, ComponentKind, SavedState};
+use leptos::logging::log;
+use leptos::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::extras::GrassSettings;
+use crate::scene::reload_scene;
+
+use super::lookup::{component_mut, resolve_component};
+use super::scatter::merge_scatter;
+use super::validate::{parse_arguments, Validator};
+use super::{ToolContext, ToolError, ToolFuture, ToolHandler};
+
+/// Shared by `addGrass` and `configureGrass`, every field left out keeps its current or default value
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct GrassArgs {
+    landscape_id: Option<String>,
+    density: Option<f32>,
+    radius: Option<f32>,
+    seed: Option<u32>,
+    blade_height: Option<f32>,
+    blade_color: Option<[f32; 3]>,
+    wind_strength: Option<f32>,
+    wind_speed: Option<f32>,
+    wind_direction: Option<[f32; 2]>,
+}
+
+impl GrassArgs {
+    fn validate(&self) -> Result<(), ToolError> {
+        let mut validator = Validator::new();
+
+        if let Some(density) = self.density {
+            validator.non_negative("density", &[density]);
+        }
+        if let Some(radius) = self.radius {
+            validator.positive("radius", &[radius]);
+        }
+        if let Some(blade_height) = self.blade_height {
+            validator.positive("bladeHeight", &[blade_height]);
+        }
+        if let Some(blade_color) = self.blade_color {
+            validator.unit_range("bladeColor", &blade_color);
+        }
+        if let Some(wind_strength) = self.wind_strength {
+            validator.non_negative("windStrength", &[wind_strength]);
+        }
+        if let Some(wind_speed) = self.wind_speed {
+            validator.finite("windSpeed", &[wind_speed]);
+        }
+        if let Some(wind_direction) = self.wind_direction {
+            validator.finite("windDirection", &wind_direction);
+        }
+
+        validator.finish()
+    }
+
+    fn apply(&self, settings: &mut GrassSettings) {
+        if let Some(blade_height) = self.blade_height {
+            settings.blade_height = blade_height;
+        }
+        if let Some(blade_color) = self.blade_color {
+            settings.blade_color = blade_color;
+        }
+        if let Some(wind_strength) = self.wind_strength {
+            settings.wind_strength = wind_strength;
+        }
+        if let Some(wind_speed) = self.wind_speed {
+            settings.wind_speed = wind_speed;
+        }
+        if let Some(wind_direction) = self.wind_direction {
+            settings.wind_direction = wind_direction;
+        }
+    }
+}
+
+fn grass_parameters() -> serde_json::Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "landscapeId": {
+                "type": "string",
+                "description": "Id of the Landscape component, may be left out when there is only one",
+            },
+            "density": { "type": "number", "minimum": 0, "description": "Blades per square unit" },
+            "radius": { "type": "number", "exclusiveMinimum": 0, "description": "Radius of the area covered" },
+            "seed": { "type": "integer", "minimum": 0 },
+            "bladeHeight": { "type": "number", "exclusiveMinimum": 0 },
+            "bladeColor": {
+                "type": "array",
+                "items": { "type": "number", "minimum": 0, "maximum": 1 },
+                "minItems": 3,
+                "maxItems": 3,
+            },
+            "windStrength": { "type": "number", "minimum": 0 },
+            "windSpeed": { "type": "number" },
+            "windDirection": {
+                "type": "array",
+                "items": { "type": "number" },
+                "minItems": 2,
+                "maxItems": 2,
+            },
+        },
+        "additionalProperties": false,
+    })
+}
+
+fn resolve_landscape(saved_state: &SavedState, landscape_id: Option<&str>) -> Result<ComponentData, ToolError> {
+    resolve_component(saved_state, "landscapeId", landscape_id, "Landscape", |c| {
+        matches!(c.kind, Some(ComponentKind::Landscape))
+    })
+}
+
+/// Writes grass onto a landscape, `create` telling whether it must be new or must already be there
+async fn set_grass(ctx: &ToolContext, args: &GrassArgs, create: bool) -> Result<serde_json::Value, ToolError> {
+    let landscape_id = {
+        let Some(pipeline_arc) = ctx.pipeline() else {
+            return Err(ToolError::failed("The project isn't loaded"));
+        };
+        let mut pipeline = pipeline_arc.borrow_mut();
+        let Some(saved_state) = pipeline.export_editor.as_mut().and_then(|e| e.saved_state.as_mut()) else {
+            return Err(ToolError::failed("The project isn't loaded"));
+        };
+
+        let landscape = resolve_landscape(saved_state, args.landscape_id.as_deref())?;
+        // the renderer grows grass wherever a landscape has a scatter
+        let has_grass = landscape.scatter.is_some();
+        match (create, has_grass) {
+            (true, true) => {
+                return Err(ToolError::invalid(
+                    "landscapeId",
+                    format!("{:?} already has grass, change it with configureGrass", landscape.id),
+                ))
+            }
+            (false, false) => {
+                return Err(ToolError::invalid(
+                    "landscapeId",
+                    format!("{:?} has no grass, add it with addGrass", landscape.id),
+                ))
+            }
+            _ => {}
+        }
+
+        let current = if create { None } else { landscape.scatter.as_ref() };
+        let scatter = merge_scatter(current, args.density, args.radius, args.seed)?;
+        if let Some(component) = component_mut(saved_state, &landscape.id) {
+            component.scatter = Some(scatter);
+        }
+
+        landscape.id
+    };
+
+    ctx.extras.update(|extras| {
+        let settings = extras.grass.entry(landscape_id.clone()).or_default();
+        args.apply(settings);
+    });
+
+    log!("Grass set on {:?}", landscape_id);
+
+    // grass is generated from the scatter settings when the project is placed, its look and wind
+    // are put on the running grass by `apply_extras`
+    if create || args.density.is_some() || args.radius.is_some() || args.seed.is_some() {
+        reload_scene(ctx).await?;
+    }
+
+    Ok(json!({ "landscapeId": landscape_id }))
+}
+
+pub struct AddGrass;
+
+impl ToolHandler for AddGrass {
+    fn name(&self) -> &'static str {
+        "addGrass"
+    }
+
+    fn description(&self) -> &'static str {
+        "Grow grass over a landscape, setting its density, blade height and color, wind and the area it covers"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        grass_parameters()
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a> {
+        Box::pin(async move {
+            let args: GrassArgs = parse_arguments(arguments)?;
+            args.validate()?;
+
+            set_grass(ctx, &args, true).await
+        })
+    }
+}
+
+pub struct ConfigureGrass;
+
+impl ToolHandler for ConfigureGrass {
+    fn name(&self) -> &'static str {
+        "configureGrass"
+    }
+
+    fn description(&self) -> &'static str {
+        "Change the density, blade height and color, wind or covered area of a landscape's grass"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        grass_parameters()
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a> {
+        Box::pin(async move {
+            let args: GrassArgs = parse_arguments(arguments)?;
+            args.validate()?;
+
+            set_grass(ctx, &args, false).await
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct RemoveGrassArgs {
+    landscape_id: Option<String>,
+}
+
+pub struct RemoveGrass;
+
+impl ToolHandler for RemoveGrass {
+    fn name(&self) -> &'static str {
+        "removeGrass"
+    }
+
+    fn description(&self) -> &'static str {
+        "Remove the grass from a landscape"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "landscapeId": {
+                    "type": "string",
+                    "description": "Id of the Landscape component, may be left out when there is only one",
+                },
+            },
+            "additionalProperties": false,
+        })
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a> {
+        Box::pin(async move {
+            let args: RemoveGrassArgs = parse_arguments(arguments)?;
+
+            let landscape_id = {
+                let Some(pipeline_arc) = ctx.pipeline() else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+                let mut pipeline = pipeline_arc.borrow_mut();
+                let Some(saved_state) = pipeline.export_editor.as_mut().and_then(|e| e.saved_state.as_mut()) else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+
+                let landscape = resolve_landscape(saved_state, args.landscape_id.as_deref())?;
+                if landscape.scatter.is_none() {
+                    return Err(ToolError::invalid(
+                        "landscapeId",
+                        format!("{:?} has no grass", landscape.id),
+                    ));
+                }
+
+                if let Some(component) = component_mut(saved_state, &landscape.id) {
+                    component.scatter = None;
+                }
+
+                landscape.id
+            };
+
+            ctx.extras.update(|extras| {
+                extras.grass.remove(&landscape_id);
+            });
+
+            reload_scene(ctx).await?;
+
+            Ok(serde_json::Value::Null)
+        })
+    }
+}
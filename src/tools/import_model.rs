@@ -0,0 +1,188 @@
+use entropy_engine::helpers::saved_data::ComponentData;
+use leptos::logging::log;
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::app::invoke_command;
+use crate::extras::ModelAsset;
+use crate::scene::reload_scene;
+
+use super::validate::{parse_arguments, Validator};
+use super::{ToolContext, ToolError, ToolFuture, ToolHandler};
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedModel {
+    pub asset_id: String,
+    pub name: String,
+    pub files: Vec<String>,
+}
+
+/// Brings a model into the project, from `source` on disk, the asset directory or a file dialog when
+/// neither is given, and places it as a Model component when there is a `position`. `None` when the
+/// dialog was cancelled.
+pub async fn import_model(
+    ctx: &ToolContext,
+    source: Option<String>,
+    library_id: Option<String>,
+    position: Option<[f32; 3]>,
+    name: Option<String>,
+) -> Result<Option<(ImportedModel, Option<String>)>, ToolError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ImportModelArgs {
+        project_id: String,
+        source: Option<String>,
+        library_id: Option<String>,
+    }
+
+    let imported: Option<ImportedModel> = invoke_command("import_model", &ImportModelArgs {
+        project_id: ctx.project_id.clone(),
+        source,
+        library_id,
+    })
+    .await?;
+    let Some(model) = imported else {
+        return Ok(None);
+    };
+
+    ctx.extras.update(|extras| {
+        extras.models.insert(model.asset_id.clone(), ModelAsset {
+            name: model.name.clone(),
+            files: model.files.clone(),
+        });
+    });
+
+    let Some(position) = position else {
+        return Ok(Some((model, None)));
+    };
+
+    let component_id = Uuid::new_v4().to_string();
+    let component: ComponentData = serde_json::from_value(json!({
+        "id": component_id,
+        "kind": "Model",
+        "asset_id": model.asset_id,
+        "generic_properties": {
+            "name": name.unwrap_or_else(|| model.name.clone()),
+            "position": position,
+            "rotation": [0.0, 0.0, 0.0],
+            "scale": [1.0, 1.0, 1.0],
+        },
+    }))
+    .map_err(|e| ToolError::failed(format!("Couldn't create the model component: {}", e)))?;
+
+    {
+        let Some(pipeline_arc) = ctx.pipeline() else {
+            return Err(ToolError::failed("The project isn't loaded"));
+        };
+        let mut pipeline = pipeline_arc.borrow_mut();
+        let Some(level) = pipeline
+            .export_editor
+            .as_mut()
+            .and_then(|e| e.saved_state.as_mut())
+            .and_then(|s| s.levels.as_mut())
+            .and_then(|l| l.get_mut(0))
+        else {
+            return Err(ToolError::failed("The project has no level to add the model to"));
+        };
+
+        level.components.get_or_insert_with(Vec::new).push(component);
+    }
+
+    log!("Placed model {:?} as {:?}", model.asset_id, component_id);
+
+    // models are loaded while placing the project
+    reload_scene(ctx).await?;
+
+    Ok(Some((model, Some(component_id))))
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ImportModelToolArgs {
+    path: Option<String>,
+    library_id: Option<String>,
+    position: Option<[f32; 3]>,
+    name: Option<String>,
+}
+
+pub struct ImportModel;
+
+impl ToolHandler for ImportModel {
+    fn name(&self) -> &'static str {
+        "importModel"
+    }
+
+    fn description(&self) -> &'static str {
+        "Import a glTF or GLB model from a file path or the asset library, optionally placing it in the scene"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string", "description": "Absolute path of a .glb or .gltf file on disk" },
+                "libraryId": {
+                    "type": "string",
+                    "description": "Asset id of a model already in the asset directory, such as one imported before",
+                },
+                "position": {
+                    "type": "array",
+                    "items": { "type": "number" },
+                    "minItems": 3,
+                    "maxItems": 3,
+                    "description": "Where to place the model, it's only imported when left out",
+                },
+                "name": { "type": "string", "description": "Name of the placed component" },
+            },
+            "additionalProperties": false,
+        })
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a> {
+        Box::pin(async move {
+            let args: ImportModelToolArgs = parse_arguments(arguments)?;
+
+            let mut validator = Validator::new();
+            match (&args.path, &args.library_id) {
+                (None, None) => validator.issue("path", "either path or libraryId is required"),
+                (Some(_), Some(_)) => validator.issue("libraryId", "can't be used together with path"),
+                _ => {}
+            }
+            if let Some(position) = args.position {
+                validator.finite("position", &position);
+            }
+            validator.finish()?;
+
+            // the dialog only opens without both, which was ruled out above
+            let imported = import_model(ctx, args.path, args.library_id, args.position, args.name)
+                .await
+                .map_err(|e| match e {
+                    // the command calls the path its source
+                    ToolError::InvalidArguments { issues } => ToolError::InvalidArguments {
+                        issues: issues
+                            .into_iter()
+                            .map(|mut issue| {
+                                if issue.field == "source" {
+                                    issue.field = "path".to_string();
+                                }
+                                issue
+                            })
+                            .collect(),
+                    },
+                    other => other,
+                })?;
+            let Some((model, component_id)) = imported else {
+                return Err(ToolError::failed("The model wasn't imported"));
+            };
+
+            Ok(json!({
+                "assetId": model.asset_id,
+                "files": model.files,
+                "componentId": component_id,
+            }))
+        })
+    }
+}
@@ -0,0 +1,299 @@
+use entropy_engine::helpers::saved_data::{ComponentData, ComponentKind};
+use leptos::logging::log;
+use leptos::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::scene::reload_scene;
+
+use super::lookup::{component_mut, resolve_component};
+use super::validate::{parse_arguments, Validator};
+use super::{ToolContext, ToolError, ToolFuture, ToolHandler};
+
+/// Shared by `addLight` and `configureLight`, every field left out keeps its current or default value
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct LightArgs {
+    // only for configureLight
+    component_id: Option<String>,
+    name: Option<String>,
+    r#type: Option<String>,
+    position: Option<[f32; 3]>,
+    color: Option<[f32; 3]>,
+    intensity: Option<f32>,
+    // unsupported, but taken so the error says why instead of calling them unknown fields
+    direction: Option<[f32; 3]>,
+    range: Option<f32>,
+    cast_shadows: Option<bool>,
+}
+
+impl LightArgs {
+    fn validate(&self) -> Result<(), ToolError> {
+        let mut validator = Validator::new();
+
+        if let Some(position) = self.position {
+            validator.finite("position", &position);
+        }
+        if let Some(color) = self.color {
+            validator.unit_range("color", &color);
+        }
+        if let Some(intensity) = self.intensity {
+            validator.non_negative("intensity", &[intensity]);
+        }
+
+        // the engine only has PointLight components, whose LightProperties are just intensity and color
+        if let Some(light_type) = self.r#type.as_deref().filter(|t| *t != "point") {
+            validator.issue("type", format!("{:?} isn't supported, the engine only has point lights", light_type));
+        }
+        if self.direction.is_some() {
+            validator.issue("direction", "isn't supported, point lights shine in every direction");
+        }
+        if self.range.is_some() {
+            validator.issue("range", "isn't supported, point lights have no range setting");
+        }
+        if self.cast_shadows.is_some() {
+            validator.issue("castShadows", "isn't supported, point lights have no shadow setting");
+        }
+
+        validator.finish()
+    }
+
+    fn apply(&self, component: &mut ComponentData) -> Result<(), ToolError> {
+        if let Some(name) = &self.name {
+            component.generic_properties.name = name.clone();
+        }
+        if let Some(position) = self.position {
+            component.generic_properties.position = position;
+        }
+
+        // LightProperties is only reachable through serde here
+        let mut properties = match component.light_properties.as_ref() {
+            Some(current) => serde_json::to_value(current).map_err(|e| ToolError::failed(e.to_string()))?,
+            None => json!({ "intensity": 1.0, "color": [1.0, 1.0, 1.0, 1.0] }),
+        };
+        if let Some(intensity) = self.intensity {
+            properties["intensity"] = json!(intensity);
+        }
+        if let Some(color) = self.color {
+            properties["color"] = json!([color[0], color[1], color[2], 1.0]);
+        }
+        component.light_properties = Some(
+            serde_json::from_value(properties)
+                .map_err(|e| ToolError::failed(format!("Couldn't build the light properties: {}", e)))?,
+        );
+
+        Ok(())
+    }
+}
+
+fn light_parameters(with_component_id: bool) -> serde_json::Value {
+    let vec3 = json!({
+        "type": "array",
+        "items": { "type": "number" },
+        "minItems": 3,
+        "maxItems": 3,
+    });
+
+    let mut properties = json!({
+        "name": { "type": "string" },
+        "type": {
+            "type": "string",
+            "enum": ["point"],
+            "description": "Only point lights exist, there are no directional (sun) lights",
+        },
+        "position": vec3,
+        "color": {
+            "type": "array",
+            "items": { "type": "number", "minimum": 0, "maximum": 1 },
+            "minItems": 3,
+            "maxItems": 3,
+        },
+        "intensity": { "type": "number", "minimum": 0 },
+    });
+
+    let mut required = Vec::new();
+    if with_component_id {
+        properties["componentId"] = json!({ "type": "string", "description": "Id of the light component" });
+        required.push("componentId");
+    }
+
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+fn is_light(component: &ComponentData) -> bool {
+    matches!(component.kind, Some(ComponentKind::PointLight))
+}
+
+pub struct AddLight;
+
+impl ToolHandler for AddLight {
+    fn name(&self) -> &'static str {
+        "addLight"
+    }
+
+    fn description(&self) -> &'static str {
+        "Add a point light with its position, color and intensity. Directional lights, range and shadows aren't supported"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        light_parameters(false)
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a> {
+        Box::pin(async move {
+            let args: LightArgs = parse_arguments(arguments)?;
+            if args.component_id.is_some() {
+                return Err(ToolError::invalid("componentId", "isn't allowed, the new light gets its own id"));
+            }
+            args.validate()?;
+
+            let component_id = Uuid::new_v4().to_string();
+            let mut component: ComponentData = serde_json::from_value(json!({
+                "id": component_id,
+                "kind": "PointLight",
+                "asset_id": "",
+                "generic_properties": {
+                    "name": "Light",
+                    "position": [0.0, 20.0, 0.0],
+                    "rotation": [0.0, 0.0, 0.0],
+                    "scale": [1.0, 1.0, 1.0],
+                },
+            }))
+            .map_err(|e| ToolError::failed(format!("Couldn't create the light component: {}", e)))?;
+
+            args.apply(&mut component)?;
+
+            {
+                let Some(pipeline_arc) = ctx.pipeline() else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+                let mut pipeline = pipeline_arc.borrow_mut();
+                let Some(level) = pipeline
+                    .export_editor
+                    .as_mut()
+                    .and_then(|e| e.saved_state.as_mut())
+                    .and_then(|s| s.levels.as_mut())
+                    .and_then(|l| l.get_mut(0))
+                else {
+                    return Err(ToolError::failed("The project has no level to add a light to"));
+                };
+
+                level.components.get_or_insert_with(Vec::new).push(component);
+            }
+
+            log!("Added light {:?}", component_id);
+
+            // lights are created while placing the project
+            reload_scene(ctx).await?;
+
+            Ok(json!({ "componentId": component_id }))
+        })
+    }
+}
+
+pub struct ConfigureLight;
+
+impl ToolHandler for ConfigureLight {
+    fn name(&self) -> &'static str {
+        "configureLight"
+    }
+
+    fn description(&self) -> &'static str {
+        "Change a point light's name, position, color or intensity"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        light_parameters(true)
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a> {
+        Box::pin(async move {
+            let args: LightArgs = parse_arguments(arguments)?;
+            let Some(component_id) = args.component_id.clone() else {
+                return Err(ToolError::invalid("componentId", "is required"));
+            };
+            args.validate()?;
+
+            {
+                let Some(pipeline_arc) = ctx.pipeline() else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+                let mut pipeline = pipeline_arc.borrow_mut();
+                let Some(saved_state) = pipeline.export_editor.as_mut().and_then(|e| e.saved_state.as_mut()) else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+
+                resolve_component(saved_state, "componentId", Some(&component_id), "PointLight", is_light)?;
+                if let Some(component) = component_mut(saved_state, &component_id) {
+                    args.apply(component)?;
+                }
+            }
+
+            reload_scene(ctx).await?;
+
+            Ok(serde_json::Value::Null)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct RemoveLightArgs {
+    component_id: String,
+}
+
+pub struct RemoveLight;
+
+impl ToolHandler for RemoveLight {
+    fn name(&self) -> &'static str {
+        "removeLight"
+    }
+
+    fn description(&self) -> &'static str {
+        "Remove a point light"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "componentId": { "type": "string", "description": "Id of the light component to remove" },
+            },
+            "required": ["componentId"],
+            "additionalProperties": false,
+        })
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a> {
+        Box::pin(async move {
+            let args: RemoveLightArgs = parse_arguments(arguments)?;
+
+            {
+                let Some(pipeline_arc) = ctx.pipeline() else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+                let mut pipeline = pipeline_arc.borrow_mut();
+                let Some(saved_state) = pipeline.export_editor.as_mut().and_then(|e| e.saved_state.as_mut()) else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+
+                resolve_component(saved_state, "componentId", Some(&args.component_id), "PointLight", is_light)?;
+                for level in saved_state.levels.iter_mut().flatten() {
+                    if let Some(components) = level.components.as_mut() {
+                        components.retain(|c| c.id != args.component_id);
+                    }
+                }
+            }
+
+            reload_scene(ctx).await?;
+
+            Ok(serde_json::Value::Null)
+        })
+    }
+}
@@ -0,0 +1,61 @@
+use entropy_engine::helpers::saved_data::{ComponentData, SavedState};
+
+use super::ToolError;
+
+pub fn components(saved_state: &SavedState) -> impl Iterator<Item = &ComponentData> {
+    saved_state
+        .levels
+        .iter()
+        .flatten()
+        .filter_map(|l| l.components.as_ref())
+        .flatten()
+}
+
+pub fn component_mut<'a>(saved_state: &'a mut SavedState, component_id: &str) -> Option<&'a mut ComponentData> {
+    saved_state
+        .levels
+        .iter_mut()
+        .flatten()
+        .filter_map(|l| l.components.as_mut())
+        .flatten()
+        .find(|c| c.id == component_id)
+}
+
+/// Picks the component of one kind a call is about, which may be left out when there is only one of them
+pub fn resolve_component(
+    saved_state: &SavedState,
+    field: &str,
+    component_id: Option<&str>,
+    kind_name: &str,
+    is_kind: impl Fn(&ComponentData) -> bool,
+) -> Result<ComponentData, ToolError> {
+    match component_id {
+        Some(component_id) => {
+            let component = components(saved_state)
+                .find(|c| c.id == component_id)
+                .ok_or_else(|| ToolError::invalid(field, format!("no component with id {:?}", component_id)))?;
+            if !is_kind(component) {
+                return Err(ToolError::invalid(
+                    field,
+                    format!("{:?} is a {:?}, not a {}", component_id, component.kind, kind_name),
+                ));
+            }
+            Ok(component.clone())
+        }
+        None => {
+            let matching: Vec<&ComponentData> = components(saved_state).filter(|c| is_kind(c)).collect();
+            match matching.as_slice() {
+                [] => Err(ToolError::failed(format!("The project has no {}", kind_name))),
+                [only] => Ok((*only).clone()),
+                many => Err(ToolError::invalid(
+                    field,
+                    format!(
+                        "is required when there is more than one {}, one of {}",
+                        kind_name,
+                        many.iter().map(|c| format!("{:?}", c.id)).collect::<Vec<_>>().join(", ")
+                    ),
+                )),
+            }
+        }
+    }
+}
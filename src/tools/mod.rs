@@ -0,0 +1,226 @@
+use entropy_engine::core::pipeline::ExportPipeline;
+use leptos::logging::log;
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::OnceLock;
+
+use crate::app::ToolCall;
+use crate::errors::{CommandError, ValidationIssue};
+use crate::extras::ProjectExtras;
+use crate::history::{self, History};
+
+mod camera;
+mod configure_water;
+mod controls;
+mod grass;
+mod import_model;
+mod lights;
+mod lookup;
+mod npcs;
+mod player;
+mod scatter;
+mod transform_object;
+mod validate;
+mod water_planes;
+
+pub use configure_water::water_plane_index;
+pub use import_model::import_model;
+
+pub type ToolFuture<'a> = Pin<Box<dyn Future<Output = Result<serde_json::Value, ToolError>> + 'a>>;
+
+/// Why a tool call didn't run, shaped so the model can correct its arguments and call again
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ToolError {
+    UnknownTool { name: String, available: Vec<String> },
+    InvalidArguments { issues: Vec<ValidationIssue> },
+    Failed { message: String },
+}
+
+impl ToolError {
+    pub fn failed(message: impl Into<String>) -> Self {
+        ToolError::Failed { message: message.into() }
+    }
+
+    pub fn invalid(field: impl Into<String>, message: impl Into<String>) -> Self {
+        ToolError::InvalidArguments {
+            issues: vec![ValidationIssue::new(field, message)],
+        }
+    }
+
+    pub fn user_message(&self) -> String {
+        match self {
+            ToolError::UnknownTool { name, .. } => format!("There is no tool called {:?}", name),
+            ToolError::InvalidArguments { issues } => issues
+                .iter()
+                .map(|i| format!("{} {}", i.field, i.message))
+                .collect::<Vec<_>>()
+                .join(", "),
+            ToolError::Failed { message } => message.clone(),
+        }
+    }
+}
+
+// saving goes through the backend, which reports bad values the same way
+impl From<CommandError> for ToolError {
+    fn from(e: CommandError) -> Self {
+        match e {
+            CommandError::Validation { issues } => ToolError::InvalidArguments { issues },
+            other => ToolError::failed(other.user_message()),
+        }
+    }
+}
+
+/// What a tool call posts back to the model, and what the chat reads to show its outcome
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ToolResult {
+    pub success: bool,
+    pub tool: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ToolError>,
+}
+
+/// Everything a tool may touch while it runs
+#[derive(Clone)]
+pub struct ToolContext {
+    pub pipeline_store: LocalResource<Option<Rc<RefCell<ExportPipeline>>>>,
+    pub project_id: String,
+    pub set_state_revision: WriteSignal<u32>,
+    pub history: RwSignal<History>,
+    pub extras: RwSignal<ProjectExtras>,
+}
+
+impl ToolContext {
+    pub fn pipeline(&self) -> Option<Rc<RefCell<ExportPipeline>>> {
+        self.pipeline_store.get_untracked().and_then(|p| p.as_ref().cloned())
+    }
+}
+
+/// One chat action the assistant can call, described by a JSON schema for its arguments
+pub trait ToolHandler: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    fn parameters(&self) -> serde_json::Value;
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a>;
+}
+
+/// The catalog entry entropy-api hands to the model, in the usual function-calling shape
+#[derive(Clone, Debug, Serialize)]
+pub struct ToolDefinition {
+    pub r#type: &'static str,
+    pub function: ToolFunctionDefinition,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ToolFunctionDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub parameters: serde_json::Value,
+}
+
+pub struct ToolRegistry {
+    handlers: BTreeMap<&'static str, Box<dyn ToolHandler>>,
+}
+
+impl ToolRegistry {
+    fn with_builtin_tools() -> Self {
+        let mut registry = ToolRegistry {
+            handlers: BTreeMap::new(),
+        };
+
+        registry.register(transform_object::TransformObject);
+        registry.register(configure_water::ConfigureWater);
+        registry.register(water_planes::AddWaterPlane);
+        registry.register(water_planes::RemoveWaterPlane);
+        registry.register(grass::AddGrass);
+        registry.register(grass::ConfigureGrass);
+        registry.register(grass::RemoveGrass);
+        registry.register(scatter::ScatterTrees);
+        registry.register(scatter::ConfigureScatter);
+        registry.register(scatter::RemoveScatter);
+        registry.register(lights::AddLight);
+        registry.register(lights::ConfigureLight);
+        registry.register(lights::RemoveLight);
+        registry.register(player::ConfigurePlayer);
+        registry.register(npcs::AddNpc);
+        registry.register(npcs::ConfigureNpc);
+        registry.register(npcs::RemoveNpc);
+        registry.register(controls::ConfigureControls);
+        registry.register(camera::ConfigureCamera);
+        registry.register(import_model::ImportModel);
+
+        registry
+    }
+
+    pub fn register(&mut self, handler: impl ToolHandler + 'static) {
+        self.handlers.insert(handler.name(), Box::new(handler));
+    }
+
+    pub fn catalog(&self) -> Vec<ToolDefinition> {
+        self.handlers
+            .values()
+            .map(|handler| ToolDefinition {
+                r#type: "function",
+                function: ToolFunctionDefinition {
+                    name: handler.name(),
+                    description: handler.description(),
+                    parameters: handler.parameters(),
+                },
+            })
+            .collect()
+    }
+
+    /// Runs a tool call and returns the JSON result that is posted back to the model
+    pub async fn execute(&self, tool_call: &ToolCall, ctx: &ToolContext) -> String {
+        log!("Executing tool call: {:?}", tool_call.function.name);
+
+        let outcome = match self.handlers.get(tool_call.function.name.as_str()) {
+            Some(handler) => {
+                let before = history::snapshot(ctx);
+                let outcome = handler.execute(ctx, &tool_call.function.arguments).await;
+                // a failed call may still have changed something before it stopped
+                history::record(ctx, tool_call.function.name.clone(), &before, &history::snapshot(ctx));
+                outcome
+            }
+            None => Err(ToolError::UnknownTool {
+                name: tool_call.function.name.clone(),
+                available: self.handlers.keys().map(|name| name.to_string()).collect(),
+            }),
+        };
+
+        let result = match outcome {
+            Ok(data) => ToolResult {
+                success: true,
+                tool: tool_call.function.name.clone(),
+                result: (!data.is_null()).then_some(data),
+                error: None,
+            },
+            Err(error) => {
+                log!("Tool call {:?} failed: {:?}", tool_call.function.name, error);
+                ToolResult {
+                    success: false,
+                    tool: tool_call.function.name.clone(),
+                    result: None,
+                    error: Some(error),
+                }
+            }
+        };
+
+        serde_json::to_string(&result).unwrap_or_else(|e| {
+            serde_json::json!({ "success": false, "tool": tool_call.function.name, "error": { "kind": "failed", "message": e.to_string() } })
+                .to_string()
+        })
+    }
+}
+
+pub fn registry() -> &'static ToolRegistry {
+    static REGISTRY: OnceLock<ToolRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(ToolRegistry::with_builtin_tools)
+}
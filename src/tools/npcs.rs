@@ -0,0 +1,360 @@
+use entropy_engine::helpers::saved_data::{ComponentData, ComponentKind, SavedState};
+use leptos::logging::log;
+use leptos::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::extras::{NpcBehavior, NpcMovement, NpcReaction};
+use crate::scene::reload_scene;
+
+use super::lookup::{component_mut, components, resolve_component};
+use super::validate::{parse_arguments, Validator};
+use super::{ToolContext, ToolError, ToolFuture, ToolHandler};
+
+/// Shared by `addNpc` and `configureNpc`, every field left out keeps its current or default value
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct NpcArgs {
+    // only for configureNpc
+    component_id: Option<String>,
+    model_id: Option<String>,
+    name: Option<String>,
+    position: Option<[f32; 3]>,
+    movement: Option<NpcMovement>,
+    wander_radius: Option<f32>,
+    patrol_waypoints: Option<Vec<[f32; 3]>>,
+    aggro_range: Option<f32>,
+    reaction: Option<NpcReaction>,
+}
+
+impl NpcArgs {
+    fn validate(&self) -> Result<(), ToolError> {
+        let mut validator = Validator::new();
+
+        if let Some(position) = self.position {
+            validator.finite("position", &position);
+        }
+        if let Some(wander_radius) = self.wander_radius {
+            validator.positive("wanderRadius", &[wander_radius]);
+        }
+        for (i, waypoint) in self.patrol_waypoints.iter().flatten().enumerate() {
+            validator.finite(&format!("patrolWaypoints[{}]", i), waypoint);
+        }
+        if let Some(aggro_range) = self.aggro_range {
+            validator.non_negative("aggroRange", &[aggro_range]);
+        }
+
+        validator.finish()
+    }
+
+    fn apply(&self, behavior: &mut NpcBehavior) -> Result<(), ToolError> {
+        if let Some(movement) = self.movement {
+            behavior.movement = movement;
+        }
+        if let Some(wander_radius) = self.wander_radius {
+            behavior.wander_radius = wander_radius;
+        }
+        if let Some(patrol_waypoints) = &self.patrol_waypoints {
+            behavior.patrol_waypoints = patrol_waypoints.clone();
+        }
+        if let Some(aggro_range) = self.aggro_range {
+            behavior.aggro_range = aggro_range;
+        }
+        if let Some(reaction) = self.reaction {
+            behavior.reaction = reaction;
+        }
+
+        // checked on the merged behavior, a call may switch to patrol with waypoints set earlier
+        if behavior.movement == NpcMovement::Patrol && behavior.patrol_waypoints.len() < 2 {
+            return Err(ToolError::invalid("patrolWaypoints", "patrolling needs at least 2 waypoints"));
+        }
+        if behavior.reaction != NpcReaction::Ignore && behavior.aggro_range <= 0.0 {
+            return Err(ToolError::invalid("aggroRange", "must be above 0 for the NPC to chase or flee"));
+        }
+
+        Ok(())
+    }
+}
+
+fn npc_parameters(with_component_id: bool) -> serde_json::Value {
+    let vec3 = json!({
+        "type": "array",
+        "items": { "type": "number" },
+        "minItems": 3,
+        "maxItems": 3,
+    });
+
+    let mut properties = json!({
+        "modelId": { "type": "string", "description": "Asset id of a model in the project" },
+        "name": { "type": "string" },
+        "position": vec3,
+        "movement": {
+            "type": "string",
+            "enum": ["idle", "wander", "patrol"],
+            "description": "Stand still, wander around where it was placed or walk between the patrol waypoints",
+        },
+        "wanderRadius": { "type": "number", "exclusiveMinimum": 0 },
+        "patrolWaypoints": { "type": "array", "items": vec3 },
+        "aggroRange": {
+            "type": "number",
+            "minimum": 0,
+            "description": "How close the player has to come to trigger the reaction",
+        },
+        "reaction": {
+            "type": "string",
+            "enum": ["ignore", "chase", "flee"],
+            "description": "What the NPC does once the player is within the aggro range",
+        },
+    });
+
+    let mut required = Vec::new();
+    if with_component_id {
+        properties["componentId"] = json!({ "type": "string", "description": "Id of the NPC component" });
+        required.push("componentId");
+    } else {
+        required.push("modelId");
+    }
+
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+fn is_npc(component: &ComponentData) -> bool {
+    matches!(component.kind, Some(ComponentKind::NPC))
+}
+
+/// The model component an NPC is made from, `NPCProperties::model_id` being its asset id
+fn find_model<'a>(saved_state: &'a SavedState, model_id: &str) -> Result<&'a ComponentData, ToolError> {
+    let models: Vec<&ComponentData> = components(saved_state)
+        .filter(|c| matches!(c.kind, Some(ComponentKind::Model)))
+        .collect();
+
+    match models.iter().find(|c| c.asset_id == model_id) {
+        Some(model) => Ok(model),
+        None => {
+            let mut available: Vec<String> = models.iter().map(|c| format!("{:?}", c.asset_id)).collect();
+            available.sort();
+            available.dedup();
+            Err(ToolError::invalid(
+                "modelId",
+                if available.is_empty() {
+                    "doesn't match a model, the project has none yet".to_string()
+                } else {
+                    format!("doesn't match a model in the project, one of {}", available.join(", "))
+                },
+            ))
+        }
+    }
+}
+
+pub struct AddNpc;
+
+impl ToolHandler for AddNpc {
+    fn name(&self) -> &'static str {
+        "addNpc"
+    }
+
+    fn description(&self) -> &'static str {
+        "Add an NPC using one of the project's models, with its movement and how it reacts to the player"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        npc_parameters(false)
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a> {
+        Box::pin(async move {
+            let args: NpcArgs = parse_arguments(arguments)?;
+            if args.component_id.is_some() {
+                return Err(ToolError::invalid("componentId", "isn't allowed, the new NPC gets its own id"));
+            }
+            let Some(model_id) = args.model_id.clone() else {
+                return Err(ToolError::invalid("modelId", "is required"));
+            };
+            args.validate()?;
+
+            let mut behavior = NpcBehavior::default();
+            args.apply(&mut behavior)?;
+
+            let component_id = Uuid::new_v4().to_string();
+
+            {
+                let Some(pipeline_arc) = ctx.pipeline() else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+                let mut pipeline = pipeline_arc.borrow_mut();
+                let Some(saved_state) = pipeline.export_editor.as_mut().and_then(|e| e.saved_state.as_mut()) else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+
+                let model = find_model(saved_state, &model_id)?;
+                let component: ComponentData = serde_json::from_value(json!({
+                    "id": component_id,
+                    "kind": "NPC",
+                    "asset_id": model_id,
+                    "generic_properties": {
+                        "name": args.name.clone().unwrap_or_else(|| format!("{} NPC", model.generic_properties.name)),
+                        "position": args.position.unwrap_or(model.generic_properties.position),
+                        "rotation": model.generic_properties.rotation,
+                        "scale": model.generic_properties.scale,
+                    },
+                    "npc_properties": { "model_id": model_id },
+                }))
+                .map_err(|e| ToolError::failed(format!("Couldn't create the NPC component: {}", e)))?;
+
+                let Some(level) = saved_state.levels.as_mut().and_then(|l| l.get_mut(0)) else {
+                    return Err(ToolError::failed("The project has no level to add an NPC to"));
+                };
+                level.components.get_or_insert_with(Vec::new).push(component);
+            }
+
+            ctx.extras.update(|extras| {
+                extras.npcs.insert(component_id.clone(), behavior);
+            });
+
+            log!("Added NPC {:?} using {:?}", component_id, model_id);
+
+            reload_scene(ctx).await?;
+
+            Ok(json!({ "componentId": component_id }))
+        })
+    }
+}
+
+pub struct ConfigureNpc;
+
+impl ToolHandler for ConfigureNpc {
+    fn name(&self) -> &'static str {
+        "configureNpc"
+    }
+
+    fn description(&self) -> &'static str {
+        "Change an NPC's model, name, position, movement, aggro range or reaction to the player"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        npc_parameters(true)
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a> {
+        Box::pin(async move {
+            let args: NpcArgs = parse_arguments(arguments)?;
+            let Some(component_id) = args.component_id.clone() else {
+                return Err(ToolError::invalid("componentId", "is required"));
+            };
+            args.validate()?;
+
+            let mut behavior = ctx
+                .extras
+                .with_untracked(|extras| extras.npcs.get(&component_id).cloned())
+                .unwrap_or_default();
+
+            {
+                let Some(pipeline_arc) = ctx.pipeline() else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+                let mut pipeline = pipeline_arc.borrow_mut();
+                let Some(saved_state) = pipeline.export_editor.as_mut().and_then(|e| e.saved_state.as_mut()) else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+
+                resolve_component(saved_state, "componentId", Some(&component_id), "NPC", is_npc)?;
+                if let Some(model_id) = &args.model_id {
+                    find_model(saved_state, model_id)?;
+                }
+                args.apply(&mut behavior)?;
+
+                if let Some(component) = component_mut(saved_state, &component_id) {
+                    if let Some(model_id) = &args.model_id {
+                        component.asset_id = model_id.clone();
+                        component.npc_properties.get_or_insert_with(Default::default).model_id = model_id.clone();
+                    }
+                    if let Some(name) = &args.name {
+                        component.generic_properties.name = name.clone();
+                    }
+                    if let Some(position) = args.position {
+                        component.generic_properties.position = position;
+                    }
+                }
+            }
+
+            ctx.extras.update(|extras| {
+                extras.npcs.insert(component_id.clone(), behavior);
+            });
+
+            // behaviors aren't drawn, only a new model or position changes the scene
+            if args.model_id.is_some() || args.position.is_some() {
+                reload_scene(ctx).await?;
+            }
+
+            Ok(serde_json::Value::Null)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct RemoveNpcArgs {
+    component_id: String,
+}
+
+pub struct RemoveNpc;
+
+impl ToolHandler for RemoveNpc {
+    fn name(&self) -> &'static str {
+        "removeNpc"
+    }
+
+    fn description(&self) -> &'static str {
+        "Remove an NPC from the scene"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "componentId": { "type": "string", "description": "Id of the NPC component to remove" },
+            },
+            "required": ["componentId"],
+            "additionalProperties": false,
+        })
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a> {
+        Box::pin(async move {
+            let args: RemoveNpcArgs = parse_arguments(arguments)?;
+
+            {
+                let Some(pipeline_arc) = ctx.pipeline() else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+                let mut pipeline = pipeline_arc.borrow_mut();
+                let Some(saved_state) = pipeline.export_editor.as_mut().and_then(|e| e.saved_state.as_mut()) else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+
+                resolve_component(saved_state, "componentId", Some(&args.component_id), "NPC", is_npc)?;
+                for level in saved_state.levels.iter_mut().flatten() {
+                    if let Some(components) = level.components.as_mut() {
+                        components.retain(|c| c.id != args.component_id);
+                    }
+                }
+            }
+
+            ctx.extras.update(|extras| {
+                extras.npcs.remove(&args.component_id);
+            });
+
+            log!("Removed NPC {:?}", args.component_id);
+
+            reload_scene(ctx).await?;
+
+            Ok(serde_json::Value::Null)
+        })
+    }
+}
@@ -0,0 +1,169 @@
+use entropy_engine::helpers::saved_data::{CollectableType, ComponentData, ComponentKind, SavedState};
+use leptos::logging::log;
+use leptos::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::scene::reload_scene;
+
+use super::lookup::{component_mut, components, resolve_component};
+use super::validate::{parse_arguments, Validator};
+use super::{ToolContext, ToolError, ToolFuture, ToolHandler};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ConfigurePlayerArgs {
+    component_id: Option<String>,
+    model_id: Option<String>,
+    // an empty string takes the weapon away
+    default_weapon_id: Option<String>,
+    spawn_position: Option<[f32; 3]>,
+    spawn_rotation: Option<[f32; 3]>,
+    // unsupported, but taken so the error says why instead of calling them unknown fields
+    walk_speed: Option<f32>,
+    run_speed: Option<f32>,
+    jump_height: Option<f32>,
+}
+
+impl ConfigurePlayerArgs {
+    fn validate(&self) -> Result<(), ToolError> {
+        let mut validator = Validator::new();
+
+        if let Some(spawn_position) = self.spawn_position {
+            validator.finite("spawnPosition", &spawn_position);
+        }
+        if let Some(spawn_rotation) = self.spawn_rotation {
+            validator.finite("spawnRotation", &spawn_rotation);
+        }
+
+        // the engine's player controller has fixed movement and PlayerProperties has nowhere to tune it
+        for (field, value) in [("walkSpeed", self.walk_speed), ("runSpeed", self.run_speed), ("jumpHeight", self.jump_height)] {
+            if value.is_some() {
+                validator.issue(field, "isn't supported, the player's movement can't be tuned yet");
+            }
+        }
+
+        validator.finish()
+    }
+}
+
+/// Checks the references a call makes against the project, which the schema can't
+fn validate_references(saved_state: &SavedState, args: &ConfigurePlayerArgs) -> Result<(), ToolError> {
+    if let Some(model_id) = &args.model_id {
+        let models: Vec<&ComponentData> = components(saved_state)
+            .filter(|c| matches!(c.kind, Some(ComponentKind::Model)))
+            .collect();
+        if !models.iter().any(|c| &c.asset_id == model_id) {
+            let mut available: Vec<String> = models.iter().map(|c| format!("{:?}", c.asset_id)).collect();
+            available.sort();
+            available.dedup();
+            return Err(ToolError::invalid(
+                "modelId",
+                if available.is_empty() {
+                    "doesn't match a model, the project has none yet".to_string()
+                } else {
+                    format!("doesn't match a model in the project, one of {}", available.join(", "))
+                },
+            ));
+        }
+    }
+
+    if let Some(weapon_id) = args.default_weapon_id.as_deref().filter(|id| !id.is_empty()) {
+        let is_weapon = |c: &ComponentData| {
+            matches!(c.kind, Some(ComponentKind::Collectable))
+                && matches!(
+                    c.collectable_properties.as_ref().and_then(|p| p.collectable_type.as_ref()),
+                    Some(CollectableType::Weapon)
+                )
+        };
+        resolve_component(saved_state, "defaultWeaponId", Some(weapon_id), "Weapon collectable", is_weapon)?;
+    }
+
+    Ok(())
+}
+
+pub struct ConfigurePlayer;
+
+impl ToolHandler for ConfigurePlayer {
+    fn name(&self) -> &'static str {
+        "configurePlayer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Set the player character's model, default weapon and spawn point. Walk and run speed and jump height can't be tuned yet"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        let vec3 = json!({
+            "type": "array",
+            "items": { "type": "number" },
+            "minItems": 3,
+            "maxItems": 3,
+        });
+
+        json!({
+            "type": "object",
+            "properties": {
+                "componentId": {
+                    "type": "string",
+                    "description": "Id of the PlayerCharacter component, may be left out when there is only one",
+                },
+                "modelId": { "type": "string", "description": "Asset id of a model in the project" },
+                "defaultWeaponId": {
+                    "type": "string",
+                    "description": "Component id of a Weapon collectable, an empty string removes the weapon",
+                },
+                "spawnPosition": vec3,
+                "spawnRotation": vec3,
+            },
+            "additionalProperties": false,
+        })
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a> {
+        Box::pin(async move {
+            let args: ConfigurePlayerArgs = parse_arguments(arguments)?;
+            args.validate()?;
+
+            let component_id = {
+                let Some(pipeline_arc) = ctx.pipeline() else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+                let mut pipeline = pipeline_arc.borrow_mut();
+                let Some(saved_state) = pipeline.export_editor.as_mut().and_then(|e| e.saved_state.as_mut()) else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+
+                let player = resolve_component(saved_state, "componentId", args.component_id.as_deref(), "PlayerCharacter", |c| {
+                    matches!(c.kind, Some(ComponentKind::PlayerCharacter))
+                })?;
+                validate_references(saved_state, &args)?;
+
+                if let Some(component) = component_mut(saved_state, &player.id) {
+                    let properties = component.player_properties.get_or_insert_with(Default::default);
+                    if let Some(model_id) = &args.model_id {
+                        properties.model_id = Some(model_id.clone());
+                    }
+                    if let Some(weapon_id) = &args.default_weapon_id {
+                        properties.default_weapon_id = (!weapon_id.is_empty()).then(|| weapon_id.clone());
+                    }
+                    if let Some(spawn_position) = args.spawn_position {
+                        component.generic_properties.position = spawn_position;
+                    }
+                    if let Some(spawn_rotation) = args.spawn_rotation {
+                        component.generic_properties.rotation = spawn_rotation;
+                    }
+                }
+
+                player.id
+            };
+
+            log!("Configured player {:?}", component_id);
+
+            // the player's model and weapon are attached while placing the project
+            reload_scene(ctx).await?;
+
+            Ok(json!({ "componentId": component_id }))
+        })
+    }
+}
@@ -0,0 +1,353 @@
+use entropy_engine::helpers::saved_data::{ComponentData, ComponentKind, ScatterSettings};
+use leptos::logging::log;
+use leptos::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::extras::ScatterRules;
+use crate::scene::reload_scene;
+
+use super::lookup::{component_mut, components};
+use super::validate::{parse_arguments, Validator};
+use super::{ToolContext, ToolError, ToolFuture, ToolHandler};
+
+/// Merges placement fields into a scatter, which only exposes them through serde here
+pub(super) fn merge_scatter(
+    current: Option<&ScatterSettings>,
+    density: Option<f32>,
+    radius: Option<f32>,
+    seed: Option<u32>,
+) -> Result<ScatterSettings, ToolError> {
+    let mut scatter = match current {
+        Some(current) => serde_json::to_value(current).map_err(|e| ToolError::failed(e.to_string()))?,
+        None => json!({
+            "density": 1.0,
+            "radius": 50.0,
+            "seed": (js_sys::Math::random() * u32::MAX as f64) as u32,
+        }),
+    };
+
+    if let Some(density) = density {
+        scatter["density"] = json!(density);
+    }
+    if let Some(radius) = radius {
+        scatter["radius"] = json!(radius);
+    }
+    if let Some(seed) = seed {
+        scatter["seed"] = json!(seed);
+    }
+
+    serde_json::from_value(scatter).map_err(|e| ToolError::failed(format!("Couldn't build the scatter settings: {}", e)))
+}
+
+fn placement_properties() -> serde_json::Map<String, serde_json::Value> {
+    let properties = json!({
+        "density": { "type": "number", "minimum": 0, "description": "Instances per square unit" },
+        "radius": { "type": "number", "exclusiveMinimum": 0, "description": "Radius of the area covered" },
+        "seed": { "type": "integer", "minimum": 0 },
+    });
+
+    match properties {
+        serde_json::Value::Object(map) => map,
+        _ => serde_json::Map::new(),
+    }
+}
+
+fn validate_placement(
+    validator: &mut Validator,
+    density: Option<f32>,
+    radius: Option<f32>,
+    avoid_water: Option<bool>,
+    max_slope_degrees: Option<f32>,
+) {
+    if let Some(density) = density {
+        validator.non_negative("density", &[density]);
+    }
+    if let Some(radius) = radius {
+        validator.positive("radius", &[radius]);
+    }
+    // the engine places instances from `ScatterSettings` alone, which can't keep them off water or slopes
+    if avoid_water == Some(true) {
+        validator.issue("avoidWater", "isn't supported, instances are placed without checking for water");
+    }
+    if max_slope_degrees.is_some() {
+        validator.issue("maxSlopeDegrees", "isn't supported, instances are placed without checking the ground's slope");
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ScatterTreesArgs {
+    asset_id: String,
+    name: Option<String>,
+    center: Option<[f32; 3]>,
+    density: Option<f32>,
+    radius: Option<f32>,
+    seed: Option<u32>,
+    avoid_water: Option<bool>,
+    max_slope_degrees: Option<f32>,
+}
+
+pub struct ScatterTrees;
+
+impl ToolHandler for ScatterTrees {
+    fn name(&self) -> &'static str {
+        "scatterTrees"
+    }
+
+    fn description(&self) -> &'static str {
+        "Scatter copies of a model, such as a tree, around an area. Instances can't be kept away from water or steep slopes"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        let mut properties = placement_properties();
+        properties.insert("assetId".to_string(), json!({
+            "type": "string",
+            "description": "Asset id of a model already in the project",
+        }));
+        properties.insert("name".to_string(), json!({ "type": "string" }));
+        properties.insert("center".to_string(), json!({
+            "type": "array",
+            "items": { "type": "number" },
+            "minItems": 3,
+            "maxItems": 3,
+        }));
+
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": ["assetId"],
+            "additionalProperties": false,
+        })
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a> {
+        Box::pin(async move {
+            let args: ScatterTreesArgs = parse_arguments(arguments)?;
+
+            let mut validator = Validator::new();
+            validate_placement(&mut validator, args.density, args.radius, args.avoid_water, args.max_slope_degrees);
+            if let Some(center) = args.center {
+                validator.finite("center", &center);
+            }
+            validator.finish()?;
+
+            let component_id = Uuid::new_v4().to_string();
+
+            {
+                let Some(pipeline_arc) = ctx.pipeline() else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+                let mut pipeline = pipeline_arc.borrow_mut();
+                let Some(saved_state) = pipeline.export_editor.as_mut().and_then(|e| e.saved_state.as_mut()) else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+
+                // the scattered copies share the source model's settings
+                let models: Vec<&ComponentData> = components(saved_state)
+                    .filter(|c| matches!(c.kind, Some(ComponentKind::Model)))
+                    .collect();
+                let Some(source) = models.iter().find(|c| c.asset_id == args.asset_id) else {
+                    let mut available: Vec<String> = models.iter().map(|c| format!("{:?}", c.asset_id)).collect();
+                    available.sort();
+                    available.dedup();
+                    return Err(ToolError::invalid(
+                        "assetId",
+                        if available.is_empty() {
+                            "doesn't match a model, the project has none yet".to_string()
+                        } else {
+                            format!("doesn't match a model in the project, one of {}", available.join(", "))
+                        },
+                    ));
+                };
+
+                let mut component = (*source).clone();
+                component.id = component_id.clone();
+                component.generic_properties.name = args.name.clone().unwrap_or_else(|| format!("{} scatter", source.generic_properties.name));
+                if let Some(center) = args.center {
+                    component.generic_properties.position = center;
+                }
+                component.scatter = Some(merge_scatter(None, args.density, args.radius, args.seed)?);
+
+                let Some(level) = saved_state.levels.as_mut().and_then(|l| l.get_mut(0)) else {
+                    return Err(ToolError::failed("The project has no level to scatter into"));
+                };
+                level.components.get_or_insert_with(Vec::new).push(component);
+            }
+
+            ctx.extras.update(|extras| {
+                extras.scatter.insert(component_id.clone(), ScatterRules { generated: true });
+            });
+
+            log!("Scattered {:?} as {:?}", args.asset_id, component_id);
+
+            // instances are generated from the scatter settings when the project is placed
+            reload_scene(ctx).await?;
+
+            Ok(json!({ "componentId": component_id }))
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ConfigureScatterArgs {
+    component_id: String,
+    density: Option<f32>,
+    radius: Option<f32>,
+    seed: Option<u32>,
+    avoid_water: Option<bool>,
+    max_slope_degrees: Option<f32>,
+}
+
+pub struct ConfigureScatter;
+
+impl ToolHandler for ConfigureScatter {
+    fn name(&self) -> &'static str {
+        "configureScatter"
+    }
+
+    fn description(&self) -> &'static str {
+        "Change the density, area or seed of a component's scatter"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        let mut properties = placement_properties();
+        properties.insert("componentId".to_string(), json!({
+            "type": "string",
+            "description": "Id of the component whose scatter to change",
+        }));
+
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": ["componentId"],
+            "additionalProperties": false,
+        })
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a> {
+        Box::pin(async move {
+            let args: ConfigureScatterArgs = parse_arguments(arguments)?;
+
+            let mut validator = Validator::new();
+            validate_placement(&mut validator, args.density, args.radius, args.avoid_water, args.max_slope_degrees);
+            validator.finish()?;
+
+            {
+                let Some(pipeline_arc) = ctx.pipeline() else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+                let mut pipeline = pipeline_arc.borrow_mut();
+                let Some(saved_state) = pipeline.export_editor.as_mut().and_then(|e| e.saved_state.as_mut()) else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+
+                let Some(component) = component_mut(saved_state, &args.component_id) else {
+                    return Err(ToolError::invalid(
+                        "componentId",
+                        format!("no component with id {:?}", args.component_id),
+                    ));
+                };
+                if matches!(component.kind, Some(ComponentKind::Landscape)) {
+                    return Err(ToolError::invalid("componentId", "is a landscape, change its grass with configureGrass"));
+                }
+                let Some(current) = component.scatter.as_ref() else {
+                    return Err(ToolError::invalid(
+                        "componentId",
+                        format!("{:?} has no scatter, create one with scatterTrees", args.component_id),
+                    ));
+                };
+
+                component.scatter = Some(merge_scatter(Some(current), args.density, args.radius, args.seed)?);
+            }
+
+            reload_scene(ctx).await?;
+
+            Ok(serde_json::Value::Null)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct RemoveScatterArgs {
+    component_id: String,
+}
+
+pub struct RemoveScatter;
+
+impl ToolHandler for RemoveScatter {
+    fn name(&self) -> &'static str {
+        "removeScatter"
+    }
+
+    fn description(&self) -> &'static str {
+        "Remove a scatter, deleting the component too when scatterTrees created it"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "componentId": { "type": "string", "description": "Id of the component whose scatter to remove" },
+            },
+            "required": ["componentId"],
+            "additionalProperties": false,
+        })
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a> {
+        Box::pin(async move {
+            let args: RemoveScatterArgs = parse_arguments(arguments)?;
+            let generated = ctx
+                .extras
+                .with_untracked(|extras| extras.scatter.get(&args.component_id).map(|r| r.generated).unwrap_or(false));
+
+            {
+                let Some(pipeline_arc) = ctx.pipeline() else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+                let mut pipeline = pipeline_arc.borrow_mut();
+                let Some(saved_state) = pipeline.export_editor.as_mut().and_then(|e| e.saved_state.as_mut()) else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+
+                let Some(component) = component_mut(saved_state, &args.component_id) else {
+                    return Err(ToolError::invalid(
+                        "componentId",
+                        format!("no component with id {:?}", args.component_id),
+                    ));
+                };
+                if matches!(component.kind, Some(ComponentKind::Landscape)) {
+                    return Err(ToolError::invalid("componentId", "is a landscape, remove its grass with removeGrass"));
+                }
+                if component.scatter.is_none() {
+                    return Err(ToolError::invalid(
+                        "componentId",
+                        format!("{:?} has no scatter", args.component_id),
+                    ));
+                }
+
+                if generated {
+                    for level in saved_state.levels.iter_mut().flatten() {
+                        if let Some(components) = level.components.as_mut() {
+                            components.retain(|c| c.id != args.component_id);
+                        }
+                    }
+                } else {
+                    component.scatter = None;
+                }
+            }
+
+            ctx.extras.update(|extras| {
+                extras.scatter.remove(&args.component_id);
+            });
+
+            reload_scene(ctx).await?;
+
+            Ok(serde_json::Value::Null)
+        })
+    }
+}
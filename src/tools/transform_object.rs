@@ -0,0 +1,147 @@
+use serde::Deserialize;
+use serde_json::json;
+
+use super::validate::{parse_arguments, Validator};
+use super::{ToolContext, ToolError, ToolFuture, ToolHandler};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct TransformObjectArgs {
+    component_id: String,
+    translation: Option<[f32; 3]>,
+    rotation: Option<[f32; 3]>,
+    scale: Option<[f32; 3]>,
+}
+
+impl TransformObjectArgs {
+    fn validate(&self) -> Result<(), ToolError> {
+        let mut validator = Validator::new();
+
+        if self.component_id.trim().is_empty() {
+            validator.issue("componentId", "must not be empty");
+        }
+        if let Some(translation) = self.translation {
+            validator.finite("translation", &translation);
+        }
+        if let Some(rotation) = self.rotation {
+            validator.finite("rotation", &rotation);
+        }
+        if let Some(scale) = self.scale {
+            validator.positive("scale", &scale);
+        }
+
+        validator.finish()
+    }
+}
+
+pub struct TransformObject;
+
+impl ToolHandler for TransformObject {
+    fn name(&self) -> &'static str {
+        "transformObject"
+    }
+
+    fn description(&self) -> &'static str {
+        "Translate, rotate or scale a model or light component"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        let vec3 = json!({
+            "type": "array",
+            "items": { "type": "number" },
+            "minItems": 3,
+            "maxItems": 3,
+        });
+        let scale = json!({
+            "type": "array",
+            "items": { "type": "number", "exclusiveMinimum": 0 },
+            "minItems": 3,
+            "maxItems": 3,
+        });
+
+        json!({
+            "type": "object",
+            "properties": {
+                "componentId": { "type": "string", "description": "Id of the component to transform" },
+                "translation": vec3,
+                "rotation": vec3,
+                "scale": scale,
+            },
+            "required": ["componentId"],
+            "additionalProperties": false,
+        })
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a> {
+        Box::pin(async move {
+            let args: TransformObjectArgs = parse_arguments(arguments)?;
+            args.validate()?;
+
+            let Some(pipeline_arc) = ctx.pipeline() else {
+                return Err(ToolError::failed("The project isn't loaded"));
+            };
+            let mut pipeline = pipeline_arc.borrow_mut();
+            let Some(editor) = pipeline.export_editor.as_mut() else {
+                return Err(ToolError::failed("The editor isn't ready"));
+            };
+
+            let known = editor.saved_state.as_ref()
+                .and_then(|s| s.levels.as_ref())
+                .map(|levels| {
+                    levels
+                        .iter()
+                        .filter_map(|l| l.components.as_ref())
+                        .flatten()
+                        .any(|c| c.id == args.component_id)
+                })
+                .unwrap_or(false);
+            if !known {
+                return Err(ToolError::invalid(
+                    "componentId",
+                    format!("no component with id {:?}", args.component_id),
+                ));
+            }
+
+            // Update SavedState
+            if let Some(saved_state) = editor.saved_state.as_mut() {
+                let component = saved_state
+                    .levels
+                    .iter_mut()
+                    .flatten()
+                    .filter_map(|l| l.components.as_mut())
+                    .flatten()
+                    .find(|c| c.id == args.component_id);
+                if let Some(component) = component {
+                    if let Some(translation) = args.translation {
+                        component.generic_properties.position = translation;
+                    }
+                    if let Some(rotation) = args.rotation {
+                        component.generic_properties.rotation = rotation;
+                    }
+                    if let Some(scale) = args.scale {
+                        component.generic_properties.scale = scale;
+                    }
+                }
+            }
+
+            // Update RendererState
+            if let Some(renderer_state) = editor.renderer_state.as_mut() {
+                if let Some(model) = renderer_state.models.iter_mut().find(|m| m.id == args.component_id) {
+                    for mesh in model.meshes.iter_mut() {
+                        if let Some(translation) = args.translation {
+                            mesh.transform.update_position(translation);
+                        }
+                        if let Some(rotation) = args.rotation {
+                            mesh.transform.update_rotation(rotation);
+                        }
+                        if let Some(scale) = args.scale {
+                            mesh.transform.update_scale(scale);
+                        }
+                    }
+                }
+            }
+
+            Ok(serde_json::Value::Null)
+        })
+    }
+}
@@ -0,0 +1,85 @@
+use serde::de::DeserializeOwned;
+
+use crate::errors::ValidationIssue;
+
+use super::ToolError;
+
+/// Decodes tool arguments, naming the field that didn't fit instead of just a line and column
+pub fn parse_arguments<T: DeserializeOwned>(arguments: &str) -> Result<T, ToolError> {
+    // some models send no arguments at all for tools whose fields are all optional
+    let arguments = if arguments.trim().is_empty() { "{}" } else { arguments };
+
+    let deserializer = &mut serde_json::Deserializer::from_str(arguments);
+    serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        let field = e.path().to_string();
+        let field = if field == "." { "arguments".to_string() } else { field };
+        ToolError::InvalidArguments {
+            issues: vec![ValidationIssue::new(field, e.into_inner().to_string())],
+        }
+    })
+}
+
+/// Collects every problem with a set of arguments so they can all be reported at once
+#[derive(Default)]
+pub struct Validator {
+    issues: Vec<ValidationIssue>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Validator::default()
+    }
+
+    pub fn issue(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.issues.push(ValidationIssue::new(field, message));
+    }
+
+    pub fn finite(&mut self, field: &str, values: &[f32]) {
+        for (i, value) in values.iter().enumerate() {
+            if !value.is_finite() {
+                self.issue(indexed(field, i, values.len()), "must be a finite number");
+            }
+        }
+    }
+
+    pub fn non_negative(&mut self, field: &str, values: &[f32]) {
+        for (i, value) in values.iter().enumerate() {
+            if !value.is_finite() || *value < 0.0 {
+                self.issue(indexed(field, i, values.len()), "must be a non-negative number");
+            }
+        }
+    }
+
+    pub fn positive(&mut self, field: &str, values: &[f32]) {
+        for (i, value) in values.iter().enumerate() {
+            if !value.is_finite() || *value <= 0.0 {
+                self.issue(indexed(field, i, values.len()), "must be greater than 0");
+            }
+        }
+    }
+
+    pub fn unit_range(&mut self, field: &str, values: &[f32]) {
+        for (i, value) in values.iter().enumerate() {
+            if !value.is_finite() || !(0.0..=1.0).contains(value) {
+                self.issue(indexed(field, i, values.len()), "must be between 0 and 1");
+            }
+        }
+    }
+
+    pub fn finish(self) -> Result<(), ToolError> {
+        if self.issues.is_empty() {
+            Ok(())
+        } else {
+            Err(ToolError::InvalidArguments { issues: self.issues })
+        }
+    }
+}
+
+// single values are reported by name, vector channels by index
+fn indexed(field: &str, index: usize, len: usize) -> String {
+    if len == 1 {
+        field.to_string()
+    } else {
+        format!("{}[{}]", field, index)
+    }
+}
@@ -0,0 +1,240 @@
+use entropy_engine::helpers::saved_data::{ComponentData, ComponentKind};
+use entropy_engine::water_plane::config::WaterConfig;
+use entropy_engine::water_plane::water::WaterPlane;
+use leptos::logging::log;
+use leptos::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use super::configure_water::{water_config_properties, water_plane_index, ConfigureWaterArgs};
+use super::lookup::resolve_component;
+use super::validate::{parse_arguments, Validator};
+use super::{ToolContext, ToolError, ToolFuture, ToolHandler};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct AddWaterPlaneArgs {
+    name: Option<String>,
+    position: Option<[f32; 3]>,
+    // width and depth, applied as the component's x and z scale
+    size: Option<[f32; 2]>,
+    config: Option<ConfigureWaterArgs>,
+}
+
+impl AddWaterPlaneArgs {
+    fn validate(&self) -> Result<(), ToolError> {
+        let mut validator = Validator::new();
+
+        if let Some(position) = self.position {
+            validator.finite("position", &position);
+        }
+        if let Some(size) = self.size {
+            validator.positive("size", &size);
+        }
+        validator.finish()?;
+
+        if let Some(config) = &self.config {
+            if config.component_id.is_some() {
+                return Err(ToolError::invalid("config.componentId", "isn't allowed, the new plane gets its own id"));
+            }
+            // report the nested fields with their full path
+            config.validate().map_err(|e| match e {
+                ToolError::InvalidArguments { issues } => ToolError::InvalidArguments {
+                    issues: issues
+                        .into_iter()
+                        .map(|mut issue| {
+                            issue.field = format!("config.{}", issue.field);
+                            issue
+                        })
+                        .collect(),
+                },
+                other => other,
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct AddWaterPlane;
+
+impl ToolHandler for AddWaterPlane {
+    fn name(&self) -> &'static str {
+        "addWaterPlane"
+    }
+
+    fn description(&self) -> &'static str {
+        "Add a new water plane to the scene, optionally with its initial water settings"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "position": {
+                    "type": "array",
+                    "items": { "type": "number" },
+                    "minItems": 3,
+                    "maxItems": 3,
+                },
+                "size": {
+                    "type": "array",
+                    "description": "Width and depth of the plane",
+                    "items": { "type": "number", "exclusiveMinimum": 0 },
+                    "minItems": 2,
+                    "maxItems": 2,
+                },
+                "config": {
+                    "type": "object",
+                    "properties": water_config_properties(),
+                    "additionalProperties": false,
+                },
+            },
+            "additionalProperties": false,
+        })
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a> {
+        Box::pin(async move {
+            let args: AddWaterPlaneArgs = parse_arguments(arguments)?;
+            args.validate()?;
+
+            let mut config = WaterConfig::default();
+            if let Some(overrides) = &args.config {
+                overrides.apply(&mut config);
+            }
+
+            let component_id = Uuid::new_v4().to_string();
+            let size = args.size.unwrap_or([1.0, 1.0]);
+            let component: ComponentData = serde_json::from_value(json!({
+                "id": component_id,
+                "kind": "WaterPlane",
+                "asset_id": "",
+                "generic_properties": {
+                    "name": args.name.clone().unwrap_or_else(|| "Water".to_string()),
+                    "position": args.position.unwrap_or([0.0, 2.0, 0.0]),
+                    "rotation": [0.0, 0.0, 0.0],
+                    "scale": [size[0], 1.0, size[1]],
+                },
+                "water_properties": config,
+            }))
+            .map_err(|e| ToolError::failed(format!("Couldn't create the water component: {}", e)))?;
+
+            {
+                let Some(pipeline_arc) = ctx.pipeline() else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+                let mut pipeline = pipeline_arc.borrow_mut();
+                let Some(editor) = pipeline.export_editor.as_mut() else {
+                    return Err(ToolError::failed("The editor isn't ready"));
+                };
+                let (Some(renderer_state), Some(gpu_resources)) = (editor.renderer_state.as_mut(), editor.gpu_resources.as_ref())
+                else {
+                    return Err(ToolError::failed("The renderer isn't ready"));
+                };
+                // the last level, so the new plane is last in component order like its renderer plane
+                let Some(level) = editor
+                    .saved_state
+                    .as_mut()
+                    .and_then(|s| s.levels.as_mut())
+                    .and_then(|l| l.last_mut())
+                else {
+                    return Err(ToolError::failed("The project has no level to add water to"));
+                };
+
+                renderer_state.water_planes.push(WaterPlane::new(
+                    &gpu_resources.device,
+                    &gpu_resources.queue,
+                    component.generic_properties.position,
+                    component.generic_properties.scale,
+                    config,
+                ));
+                level.components.get_or_insert_with(Vec::new).push(component);
+            }
+
+            log!("Added water plane {:?}", component_id);
+
+            ctx.set_state_revision.update(|revision| *revision += 1);
+
+            Ok(json!({ "componentId": component_id }))
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct RemoveWaterPlaneArgs {
+    component_id: String,
+}
+
+pub struct RemoveWaterPlane;
+
+impl ToolHandler for RemoveWaterPlane {
+    fn name(&self) -> &'static str {
+        "removeWaterPlane"
+    }
+
+    fn description(&self) -> &'static str {
+        "Remove a water plane from the scene"
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "componentId": { "type": "string", "description": "Id of the WaterPlane component to remove" },
+            },
+            "required": ["componentId"],
+            "additionalProperties": false,
+        })
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a ToolContext, arguments: &'a str) -> ToolFuture<'a> {
+        Box::pin(async move {
+            let args: RemoveWaterPlaneArgs = parse_arguments(arguments)?;
+
+            {
+                let Some(pipeline_arc) = ctx.pipeline() else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+                let mut pipeline = pipeline_arc.borrow_mut();
+                let Some(editor) = pipeline.export_editor.as_mut() else {
+                    return Err(ToolError::failed("The editor isn't ready"));
+                };
+                let Some(saved_state) = editor.saved_state.as_mut() else {
+                    return Err(ToolError::failed("The project isn't loaded"));
+                };
+
+                resolve_component(
+                    saved_state,
+                    "componentId",
+                    Some(&args.component_id),
+                    "WaterPlane",
+                    |c| matches!(c.kind, Some(ComponentKind::WaterPlane)),
+                )?;
+
+                let index = water_plane_index(saved_state, &args.component_id);
+
+                for level in saved_state.levels.iter_mut().flatten() {
+                    if let Some(components) = level.components.as_mut() {
+                        components.retain(|c| c.id != args.component_id);
+                    }
+                }
+
+                if let (Some(index), Some(renderer_state)) = (index, editor.renderer_state.as_mut()) {
+                    if index < renderer_state.water_planes.len() {
+                        renderer_state.water_planes.remove(index);
+                    }
+                }
+            }
+
+            log!("Removed water plane {:?}", args.component_id);
+
+            ctx.set_state_revision.update(|revision| *revision += 1);
+
+            Ok(serde_json::Value::Null)
+        })
+    }
+}